@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::error::err;
+use crate::seq::edit_distance;
+
+/// Mitochondrial chromosome names, all taken as the same.
+const MITOCHONDRIA: [&str; 4] = ["chrM", "MT", "chrMT", "M"];
+
+/// Chromosome name aliases, e.g. GRCh38 `2`, UCSC `chr2` and RefSeq accession `NC_000002.12`.
+///
+/// ## Note
+///
+/// Names differing only by `chr` prefix are always taken as the same, so are `chrM` and `MT`.
+#[derive(Default, Clone)]
+pub struct Aliases {
+    /// Equivalent names of a name, excluding itself.
+    names: HashMap<String, Vec<String>>,
+}
+
+impl Aliases {
+    /// Read aliases from a tab-separated file, names on the same line are equivalent.
+    ///
+    /// ## Format
+    ///
+    /// > 2\tchr2\tNC_000002.12
+    ///
+    /// ## Note
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn from_path(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut names: HashMap<String, Vec<String>> = HashMap::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            };
+            let group: Vec<&str> = line.split('\t').map(|i| i.trim()).collect();
+            if group.len() < 2 || group.iter().any(|i| i.is_empty()) {
+                return Err(Box::new(err(&format!(
+                    "Error parsing `{}` as a chromosome alias line of `{}`.",
+                    line, path
+                ))));
+            };
+            for name in &group {
+                names.entry(String::from(*name)).or_default().extend(
+                    group
+                        .iter()
+                        .filter(|i| *i != name)
+                        .map(|i| String::from(*i)),
+                );
+            }
+        }
+        Ok(Self { names })
+    }
+
+    /// Equivalent names of a name, the name itself first.
+    fn candidates(&self, name: &str) -> Vec<String> {
+        let mut r: Vec<String> = vec![String::from(name)];
+        if let Some(v) = self.names.get(name) {
+            r.extend(v.iter().cloned());
+        };
+        for each in r.clone() {
+            match each.strip_prefix("chr") {
+                Some(v) => r.push(String::from(v)),
+                None => r.push(format!("chr{}", each)),
+            };
+            if MITOCHONDRIA.contains(&each.as_str()) {
+                r.extend(MITOCHONDRIA.iter().map(|i| String::from(*i)));
+            };
+        }
+        let mut seen: Vec<String> = Vec::with_capacity(r.len());
+        r.retain(|i| {
+            let new = !seen.contains(i);
+            seen.push(i.clone());
+            new
+        });
+        r
+    }
+
+    /// Name among `known` equivalent to `name`, `None` if not any.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use vav::alias::Aliases;
+    /// let aliases = Aliases::default();
+    /// assert_eq!(aliases.resolve("chr1", &["1", "2"]), Some("1"));
+    /// ```
+    pub fn resolve<'a, S: AsRef<str>>(&self, name: &str, known: &'a [S]) -> Option<&'a str> {
+        self.candidates(name)
+            .iter()
+            .find_map(|i| known.iter().map(|j| j.as_ref()).find(|j| *j == i.as_str()))
+    }
+
+    /// Like `resolve`, but error listing close names among `known` of `source` if not any.
+    pub fn resolve_or_suggest<'a, S: AsRef<str>>(
+        &self,
+        name: &str,
+        known: &'a [S],
+        source: &str,
+    ) -> Result<&'a str, Box<dyn Error>> {
+        if let Some(v) = self.resolve(name, known) {
+            return Ok(v);
+        };
+        let mut close: Vec<(usize, &str)> = known
+            .iter()
+            .map(|i| {
+                let i = i.as_ref();
+                (edit_distance(name.as_bytes(), i.as_bytes()), i)
+            })
+            .filter(|(d, _)| *d <= 3)
+            .collect();
+        close.sort_unstable();
+        Err(Box::new(err(&format!(
+            "Chromosome `{}` not found in {}{}.",
+            name,
+            source,
+            if close.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    ", close matches: {}",
+                    close
+                        .iter()
+                        .take(5)
+                        .map(|(_, i)| format!("`{}`", i))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let mut aliases = Aliases::default();
+        aliases.names.insert(
+            String::from("NC_000002.12"),
+            vec![String::from("2"), String::from("chr2")],
+        );
+        let known = ["1", "2", "MT", "chr10"];
+        assert_eq!(aliases.resolve("chr1", &known), Some("1"));
+        assert_eq!(aliases.resolve("chrM", &known), Some("MT"));
+        assert_eq!(aliases.resolve("NC_000002.12", &known), Some("2"));
+        assert_eq!(aliases.resolve("10", &known), Some("chr10"));
+        let e = aliases
+            .resolve_or_suggest("chr11", &known, "bam")
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("close matches: `chr10`"));
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use bam::bgzip::SeekReader;
+use bam::header::{EntryType, Header as BamHeader, HeaderLine};
+use bam::index::region_to_bin;
+use bam::record::Record as BamRecord;
+use bam::{BamReader, BamWriter, RecordWriter};
+
+use crate::summary::Category;
+
+/// Tag of read categories, e.g. `YV:Z:proper`.
+pub const CATEGORY_TAG: &[u8; 2] = b"YV";
+
+/// Tag of variants a read counted for, in the same order with `CATEGORY_TAG`.
+pub const VARIANT_TAG: &[u8; 2] = b"YK";
+
+/// Bit shift of BAI linear index windows, 16 kb each.
+const LINEAR_SHIFT: i32 = 14;
+
+/// Reads counted for variants, written as a sorted and indexed bam file.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use bam::record::Record as BamRecord;
+/// # use vav::bamout::BamOut;
+/// # use vav::summary::Category;
+/// # let record = BamRecord::new();
+/// let mut out = BamOut::from_bam("tumor.bam", "vav --alt-bam alt.bam")?;
+/// out.add("1:300T>A", Category::Proper, &record);
+/// out.write("alt.bam")?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// ## Note
+///
+/// A read counted for several variants is written once, its categories and variants comma
+/// separated, e.g. `YV:Z:proper,reference` and `YK:Z:1:300T>A,1:302G>C`.
+pub struct BamOut {
+    header: BamHeader,
+    reads: Vec<(BamRecord, Vec<&'static str>, Vec<String>)>,
+    /// Index of reads, by read name, flag, reference id and start.
+    seen: HashMap<(Vec<u8>, u16, i32, i32), usize>,
+}
+
+impl BamOut {
+    /// Output of reads with `header`, and a `@PG` line of command line `command`.
+    pub fn new(mut header: BamHeader, command: &str) -> Result<Self, Box<dyn Error>> {
+        let programs: Vec<&str> = header
+            .lines()
+            .filter_map(|i| match i {
+                HeaderLine::Entry(v) if v.entry_type() == EntryType::Program => v.get(b"ID"),
+                _ => None,
+            })
+            .collect();
+        let mut id = String::from("vav");
+        let mut n = 0;
+        while programs.contains(&id.as_str()) {
+            n += 1;
+            id = format!("vav.{}", n);
+        }
+        let line = format!(
+            "@PG\tID:{}\tPN:vav\tVN:{}\tCL:{}",
+            id,
+            env!("CARGO_PKG_VERSION"),
+            command
+        );
+        header.push_line(&line)?;
+        Ok(Self {
+            header,
+            reads: Vec::new(),
+            seen: HashMap::new(),
+        })
+    }
+
+    /// Output of reads with header of bam file `bam`, see `new`.
+    pub fn from_bam(bam: &str, command: &str) -> Result<Self, Box<dyn Error>> {
+        let reader = BamReader::from_path(bam, 0)?;
+        Self::new(reader.header().clone(), command)
+    }
+
+    /// Add a read counted for `variant` in `category`.
+    pub fn add(&mut self, variant: &str, category: Category, record: &BamRecord) {
+        let key = (
+            record.name().to_vec(),
+            record.flag().0,
+            record.ref_id(),
+            record.start(),
+        );
+        let index = match self.seen.get(&key) {
+            Some(v) => *v,
+            None => {
+                self.seen.insert(key, self.reads.len());
+                self.reads.push((record.clone(), Vec::new(), Vec::new()));
+                self.reads.len() - 1
+            }
+        };
+        let (_, categories, variants) = &mut self.reads[index];
+        categories.push(category.as_str());
+        variants.push(String::from(variant));
+    }
+
+    pub fn len(&self) -> usize {
+        self.reads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty()
+    }
+
+    /// Write reads sorted by coordinate to `path`, indexed as `path.bai`.
+    pub fn write(mut self, path: &str) -> Result<(), Box<dyn Error>> {
+        self.reads
+            .sort_by_key(|(i, _, _)| (i.ref_id() as u32, i.start(), i.name().to_vec()));
+        let mut writer = BamWriter::from_path(path, self.header)?;
+        for (mut record, categories, variants) in self.reads {
+            let tags = record.tags_mut();
+            tags.remove(CATEGORY_TAG);
+            tags.remove(VARIANT_TAG);
+            tags.push_string(CATEGORY_TAG, categories.join(",").as_bytes());
+            tags.push_string(VARIANT_TAG, variants.join(",").as_bytes());
+            writer.write(&record)?;
+        }
+        writer.finish()?;
+        index(path)
+    }
+}
+
+/// Chunks and linear index of a reference in BAI.
+#[derive(Default)]
+struct RefIndex {
+    /// Chunks of virtual offsets, by bin.
+    bins: BTreeMap<u32, Vec<(u64, u64)>>,
+    /// Smallest virtual offset of reads overlapping each 16 kb window.
+    linear: Vec<u64>,
+}
+
+/// BAI index of a coordinate sorted bam file.
+struct Bai {
+    refs: Vec<RefIndex>,
+}
+
+impl Bai {
+    fn new(n_ref: usize) -> Self {
+        Self {
+            refs: (0..n_ref).map(|_| RefIndex::default()).collect(),
+        }
+    }
+
+    /// Add a read of reference `rid` at 0-based `[beg, end)`, stored at virtual offsets `[from, to)`.
+    fn add(&mut self, rid: usize, beg: i32, end: i32, from: u64, to: u64) {
+        let end = end.max(beg + 1);
+        let reference = &mut self.refs[rid];
+        let chunks = reference.bins.entry(region_to_bin(beg, end)).or_default();
+        match chunks.last_mut() {
+            Some(v) if v.1 == from => v.1 = to,
+            _ => chunks.push((from, to)),
+        };
+        let last = ((end - 1) >> LINEAR_SHIFT) as usize;
+        if reference.linear.len() <= last {
+            reference.linear.resize(last + 1, 0);
+        };
+        for i in (beg >> LINEAR_SHIFT) as usize..=last {
+            if reference.linear[i] == 0 {
+                reference.linear[i] = from;
+            };
+        }
+    }
+
+    /// Write in BAI format, windows without reads taking offset of the previous window.
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), Box<dyn Error>> {
+        writer.write_all(b"BAI\x01")?;
+        writer.write_all(&(self.refs.len() as i32).to_le_bytes())?;
+        for reference in &self.refs {
+            writer.write_all(&(reference.bins.len() as i32).to_le_bytes())?;
+            for (bin, chunks) in &reference.bins {
+                writer.write_all(&bin.to_le_bytes())?;
+                writer.write_all(&(chunks.len() as i32).to_le_bytes())?;
+                for (from, to) in chunks {
+                    writer.write_all(&from.to_le_bytes())?;
+                    writer.write_all(&to.to_le_bytes())?;
+                }
+            }
+            writer.write_all(&(reference.linear.len() as i32).to_le_bytes())?;
+            let mut prev = 0;
+            for v in &reference.linear {
+                if *v != 0 {
+                    prev = *v;
+                };
+                writer.write_all(&prev.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Index a coordinate sorted bam file as `path.bai`.
+///
+/// ## Note
+///
+/// Unmapped reads are not indexed.
+pub fn index(path: &str) -> Result<(), Box<dyn Error>> {
+    let mut reader = SeekReader::from_path(path, 0)?;
+    reader.make_consecutive();
+    let header = BamHeader::from_bam(&mut reader)?;
+    let mut bai = Bai::new(header.n_references());
+    let mut record = BamRecord::new();
+    loop {
+        let from = reader.current_offset().raw();
+        if !record.fill_from_bam(&mut reader)? {
+            break;
+        };
+        let to = reader.current_offset().raw();
+        if record.flag().is_mapped() && record.ref_id() >= 0 {
+            bai.add(
+                record.ref_id() as usize,
+                record.start(),
+                record.calculate_end(),
+                from,
+                to,
+            );
+        };
+    }
+    let mut writer = BufWriter::new(File::create(format!("{}.bai", path))?);
+    bai.write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bai() {
+        let mut bai = Bai::new(1);
+        bai.add(0, 100, 200, 10, 20);
+        bai.add(0, 150, 250, 20, 30);
+        bai.add(0, 16380, 16400, 30, 40);
+        let reference = &bai.refs[0];
+        assert_eq!(reference.bins[&4681], vec![(10, 30)]);
+        assert_eq!(reference.bins.len(), 2);
+        assert_eq!(reference.linear, vec![10, 30]);
+
+        let mut bytes: Vec<u8> = Vec::new();
+        bai.write(&mut bytes).unwrap();
+        // Magic, n_ref, n_bin, 2 bins of 1 chunk each, n_intv and 2 offsets.
+        assert_eq!(bytes.len(), 4 + 4 + 4 + 2 * (4 + 4 + 16) + 4 + 2 * 8);
+        assert_eq!(&bytes[..4], b"BAI\x01");
+    }
+}
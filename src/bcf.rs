@@ -0,0 +1,511 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::io::{ErrorKind, Read};
+
+use crate::error::err;
+
+/// Missing and end of vector values of `i8`, `i16` and `i32`, and bits of `f32`.
+const INT8_MISSING: i32 = -128;
+const INT8_END: i32 = -127;
+const INT16_MISSING: i32 = -32768;
+const INT16_END: i32 = -32767;
+const INT32_MISSING: i32 = i32::MIN;
+const INT32_END: i32 = i32::MIN + 1;
+const FLOAT_MISSING: u32 = 0x7F80_0001;
+const FLOAT_END: u32 = 0x7F80_0002;
+
+/// Values of a typed BCF field.
+#[derive(PartialEq, Debug)]
+enum Values {
+    /// Integers, missing values as `None`, end of vector padding dropped.
+    Ints(Vec<Option<i32>>),
+    Floats(Vec<Option<f32>>),
+    /// Characters, trailing `\0` padding dropped.
+    Chars(String),
+}
+
+impl Values {
+    /// Value as in VCF, `.` if missing.
+    fn to_text(&self) -> String {
+        let join = |v: Vec<String>| {
+            if v.is_empty() {
+                String::from(".")
+            } else {
+                v.join(",")
+            }
+        };
+        match self {
+            Self::Ints(v) => join(v.iter().map(|i| or_dot(i.map(|j| j.to_string()))).collect()),
+            Self::Floats(v) => join(v.iter().map(|i| or_dot(i.map(|j| j.to_string()))).collect()),
+            Self::Chars(v) if v.is_empty() => String::from("."),
+            Self::Chars(v) => v.clone(),
+        }
+    }
+
+    /// Genotype as in VCF, alleles encoded as `(allele + 1) << 1 | phased`.
+    fn to_genotype(&self) -> String {
+        let alleles = match self {
+            Self::Ints(v) if !v.is_empty() => v,
+            _ => return String::from("."),
+        };
+        let mut r = String::new();
+        for (i, v) in alleles.iter().enumerate() {
+            if i > 0 {
+                r.push(if v.map_or(0, |j| j & 1) == 1 {
+                    '|'
+                } else {
+                    '/'
+                });
+            };
+            match v.map(|j| (j >> 1) - 1) {
+                Some(a) if a >= 0 => r.push_str(&a.to_string()),
+                _ => r.push('.'),
+            };
+        }
+        r
+    }
+}
+
+fn or_dot(v: Option<String>) -> String {
+    v.unwrap_or_else(|| String::from("."))
+}
+
+/// Bytes of a BCF record, read from the front.
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        let v = self
+            .bytes
+            .get(self.at..self.at + n)
+            .ok_or_else(|| err("Error parsing BCF record, record truncated."))?;
+        self.at += n;
+        Ok(v)
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        let mut v = [0u8; 4];
+        v.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(v))
+    }
+
+    fn i32(&mut self) -> Result<i32, Box<dyn Error>> {
+        Ok(self.u32()? as i32)
+    }
+
+    /// Type and number of values of a typed field.
+    fn descriptor(&mut self) -> Result<(u8, usize), Box<dyn Error>> {
+        let v = self.take(1)?[0];
+        let (kind, n) = (v & 0x0F, (v >> 4) as usize);
+        if n < 15 {
+            return Ok((kind, n));
+        };
+        // Number of values not fitting in 4 bits follows as a typed integer.
+        match self.typed()? {
+            Values::Ints(v) if v.len() == 1 && v[0].is_some_and(|i| i >= 0) => {
+                Ok((kind, v[0].unwrap_or(0) as usize))
+            }
+            _ => Err(Box::new(err(
+                "Error parsing BCF record, invalid vector length.",
+            ))),
+        }
+    }
+
+    /// `n` values of type `kind`.
+    fn values(&mut self, kind: u8, n: usize) -> Result<Values, Box<dyn Error>> {
+        let int = |v: i32, missing: i32, end: i32| match v {
+            _ if v == end => None,
+            _ if v == missing => Some(None),
+            _ => Some(Some(v)),
+        };
+        Ok(match kind {
+            0 => Values::Ints(Vec::new()),
+            1 => Values::Ints(
+                self.take(n)?
+                    .iter()
+                    .filter_map(|i| int(*i as i8 as i32, INT8_MISSING, INT8_END))
+                    .collect(),
+            ),
+            2 => Values::Ints(
+                self.take(2 * n)?
+                    .chunks(2)
+                    .filter_map(|i| {
+                        int(
+                            i16::from_le_bytes([i[0], i[1]]) as i32,
+                            INT16_MISSING,
+                            INT16_END,
+                        )
+                    })
+                    .collect(),
+            ),
+            3 => Values::Ints(
+                self.take(4 * n)?
+                    .chunks(4)
+                    .filter_map(|i| {
+                        int(
+                            i32::from_le_bytes([i[0], i[1], i[2], i[3]]),
+                            INT32_MISSING,
+                            INT32_END,
+                        )
+                    })
+                    .collect(),
+            ),
+            5 => Values::Floats(
+                self.take(4 * n)?
+                    .chunks(4)
+                    .filter_map(|i| match u32::from_le_bytes([i[0], i[1], i[2], i[3]]) {
+                        FLOAT_END => None,
+                        FLOAT_MISSING => Some(None),
+                        v => Some(Some(f32::from_bits(v))),
+                    })
+                    .collect(),
+            ),
+            7 => {
+                let v = self.take(n)?;
+                let end = v.iter().position(|i| *i == 0).unwrap_or(v.len());
+                Values::Chars(String::from_utf8_lossy(&v[..end]).into_owned())
+            }
+            _ => {
+                return Err(Box::new(err(&format!(
+                    "Error parsing BCF record, unknown value type `{}`.",
+                    kind
+                ))))
+            }
+        })
+    }
+
+    fn typed(&mut self) -> Result<Values, Box<dyn Error>> {
+        let (kind, n) = self.descriptor()?;
+        self.values(kind, n)
+    }
+
+    /// A typed integer, e.g. index of a dictionary.
+    fn index(&mut self) -> Result<usize, Box<dyn Error>> {
+        match self.typed()? {
+            Values::Ints(v) if v.len() == 1 && v[0].is_some_and(|i| i >= 0) => {
+                Ok(v[0].unwrap_or(0) as usize)
+            }
+            _ => Err(Box::new(err(
+                "Error parsing BCF record, invalid dictionary index.",
+            ))),
+        }
+    }
+}
+
+/// Attributes of a structured meta line, e.g. `##INFO=<ID=DP,Number=1,...>`.
+fn attributes(line: &str) -> HashMap<&str, &str> {
+    let body = match (line.find('<'), line.rfind('>')) {
+        (Some(a), Some(b)) if a < b => &line[a + 1..b],
+        _ => return HashMap::new(),
+    };
+    let mut r = HashMap::new();
+    let (mut from, mut quoted) = (0, false);
+    for (i, c) in body
+        .char_indices()
+        .chain(std::iter::once((body.len(), ',')))
+    {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                if let Some((k, v)) = body[from..i].split_once('=') {
+                    r.insert(k, v);
+                };
+                from = i + 1;
+            }
+            _ => {}
+        };
+    }
+    r
+}
+
+/// Header of BCF, the VCF header text and dictionaries of its IDs.
+struct Header {
+    text: String,
+    /// IDs of FILTER, INFO and FORMAT, by index.
+    strings: HashMap<usize, String>,
+    contigs: HashMap<usize, String>,
+    /// INFO IDs of Type `Flag`.
+    flags: HashSet<String>,
+}
+
+impl Header {
+    /// Dictionaries by header lines in order, or by `IDX` if given.
+    ///
+    /// ## Note
+    ///
+    /// `PASS` takes index 0 of FILTER, INFO and FORMAT IDs, declared or not.
+    fn new(text: String) -> Self {
+        let mut strings: HashMap<usize, String> = HashMap::new();
+        let mut contigs: HashMap<usize, String> = HashMap::new();
+        let mut flags: HashSet<String> = HashSet::new();
+        strings.insert(0, String::from("PASS"));
+        for line in text.lines() {
+            let attrs = attributes(line);
+            let (id, idx) = match attrs.get("ID") {
+                Some(v) => (*v, attrs.get("IDX").and_then(|i| i.parse::<usize>().ok())),
+                None => continue,
+            };
+            if line.starts_with("##contig=") {
+                let i = idx.unwrap_or_else(|| contigs.keys().max().map_or(0, |j| j + 1));
+                contigs.insert(i, String::from(id));
+                continue;
+            } else if !["##FILTER=", "##INFO=", "##FORMAT="]
+                .iter()
+                .any(|i| line.starts_with(i))
+            {
+                continue;
+            };
+            if line.starts_with("##INFO=") && attrs.get("Type") == Some(&"Flag") {
+                flags.insert(String::from(id));
+            };
+            match idx {
+                Some(i) => {
+                    strings.insert(i, String::from(id));
+                }
+                None if !strings.values().any(|i| i == id) => {
+                    let i = strings.keys().max().map_or(0, |j| j + 1);
+                    strings.insert(i, String::from(id));
+                }
+                None => {}
+            };
+        }
+        Self {
+            text,
+            strings,
+            contigs,
+            flags,
+        }
+    }
+
+    fn string(&self, i: usize) -> Result<&str, Box<dyn Error>> {
+        match self.strings.get(&i) {
+            Some(v) => Ok(v),
+            None => Err(Box::new(err(&format!(
+                "Error parsing BCF record, ID of index `{}` not in header.",
+                i
+            )))),
+        }
+    }
+
+    /// VCF data line of a BCF record, `shared` and `indiv` its two parts.
+    fn line(&self, shared: &[u8], indiv: &[u8]) -> Result<String, Box<dyn Error>> {
+        let mut cursor = Cursor {
+            bytes: shared,
+            at: 0,
+        };
+        let chrom = cursor.i32()?;
+        let chrom = self.contigs.get(&(chrom as usize)).ok_or_else(|| {
+            err(&format!(
+                "Error parsing BCF record, contig of index `{}` not in header.",
+                chrom
+            ))
+        })?;
+        let pos = cursor.i32()? + 1;
+        let _rlen = cursor.i32()?;
+        let qual = match cursor.u32()? {
+            FLOAT_MISSING => String::from("."),
+            v => f32::from_bits(v).to_string(),
+        };
+        let counts = cursor.u32()?;
+        let (n_info, n_allele) = ((counts & 0xFFFF) as usize, (counts >> 16) as usize);
+        let counts = cursor.u32()?;
+        let (n_sample, n_fmt) = ((counts & 0xFF_FFFF) as usize, (counts >> 24) as usize);
+
+        let id = cursor.typed()?.to_text();
+        let mut alleles: Vec<String> = Vec::with_capacity(n_allele);
+        for _ in 0..n_allele {
+            alleles.push(cursor.typed()?.to_text());
+        }
+        let refs = alleles
+            .first()
+            .cloned()
+            .unwrap_or_else(|| String::from("."));
+        let alts = if alleles.len() > 1 {
+            alleles[1..].join(",")
+        } else {
+            String::from(".")
+        };
+        let filters: Vec<String> = match cursor.typed()? {
+            Values::Ints(v) => v
+                .iter()
+                .flatten()
+                .map(|i| self.string(*i as usize).map(String::from))
+                .collect::<Result<_, _>>()?,
+            _ => Vec::new(),
+        };
+        let mut info: Vec<String> = Vec::with_capacity(n_info);
+        for _ in 0..n_info {
+            let key = self.string(cursor.index()?)?;
+            let value = cursor.typed()?;
+            if self.flags.contains(key) {
+                info.push(String::from(key));
+            } else {
+                info.push(format!("{}={}", key, value.to_text()));
+            };
+        }
+        let mut fields = vec![
+            chrom.clone(),
+            pos.to_string(),
+            id,
+            refs,
+            alts,
+            qual,
+            if filters.is_empty() {
+                String::from(".")
+            } else {
+                filters.join(";")
+            },
+            if info.is_empty() {
+                String::from(".")
+            } else {
+                info.join(";")
+            },
+        ];
+        if n_fmt == 0 {
+            return Ok(fields.join("\t"));
+        };
+
+        let mut cursor = Cursor {
+            bytes: indiv,
+            at: 0,
+        };
+        let mut keys: Vec<&str> = Vec::with_capacity(n_fmt);
+        let mut samples: Vec<Vec<String>> = vec![Vec::with_capacity(n_fmt); n_sample];
+        for _ in 0..n_fmt {
+            let key = self.string(cursor.index()?)?;
+            let (kind, n) = cursor.descriptor()?;
+            for sample in samples.iter_mut() {
+                let values = cursor.values(kind, n)?;
+                sample.push(if key == "GT" {
+                    values.to_genotype()
+                } else {
+                    values.to_text()
+                });
+            }
+            keys.push(key);
+        }
+        fields.push(keys.join(":"));
+        fields.extend(samples.iter().map(|i| i.join(":")));
+        Ok(fields.join("\t"))
+    }
+}
+
+/// Fill `buf` from `reader`, `false` if at the end before any byte read.
+fn fill<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, Box<dyn Error>> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) if n == 0 => return Ok(false),
+            Ok(0) => return Err(Box::new(err("Error parsing BCF, file truncated."))),
+            Ok(v) => n += v,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(Box::new(e)),
+        };
+    }
+    Ok(true)
+}
+
+/// Read BCF, decompressed, as VCF header text and data lines.
+///
+/// ## Warn
+///
+/// Only BCF 2 is supported, e.g. by `bcftools view -Ob`.
+pub fn read<R: Read>(mut reader: R) -> Result<(String, Vec<String>), Box<dyn Error>> {
+    let mut magic = [0u8; 5];
+    if !fill(&mut reader, &mut magic)? || &magic[..3] != b"BCF" || magic[3] != 2 {
+        return Err(Box::new(err("Error parsing BCF, only BCF 2 is supported.")));
+    };
+    let mut len = [0u8; 4];
+    fill(&mut reader, &mut len)?;
+    let mut text = vec![0u8; u32::from_le_bytes(len) as usize];
+    fill(&mut reader, &mut text)?;
+    let end = text.iter().position(|i| *i == 0).unwrap_or(text.len());
+    let header = Header::new(String::from_utf8_lossy(&text[..end]).into_owned());
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut lens = [0u8; 8];
+    while fill(&mut reader, &mut lens)? {
+        let shared = u32::from_le_bytes([lens[0], lens[1], lens[2], lens[3]]) as usize;
+        let indiv = u32::from_le_bytes([lens[4], lens[5], lens[6], lens[7]]) as usize;
+        let mut record = vec![0u8; shared + indiv];
+        fill(&mut reader, &mut record)?;
+        lines.push(header.line(&record[..shared], &record[shared..])?);
+    }
+    Ok((header.text, lines))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Typed BCF field of `int8` values.
+    fn ints(v: &[i8]) -> Vec<u8> {
+        let mut r = vec![(v.len() as u8) << 4 | 1];
+        r.extend(v.iter().map(|i| *i as u8));
+        r
+    }
+
+    /// Typed BCF field of characters.
+    fn chars(v: &str) -> Vec<u8> {
+        let mut r = vec![(v.len() as u8) << 4 | 7];
+        r.extend(v.as_bytes());
+        r
+    }
+
+    #[test]
+    fn test_read() {
+        let text = "##fileformat=VCFv4.2\n\
+            ##FILTER=<ID=PASS,Description=\"All filters passed\">\n\
+            ##INFO=<ID=DP,Number=1,Type=Integer,Description=\"Depth, total\">\n\
+            ##INFO=<ID=DB,Number=0,Type=Flag,Description=\"dbSNP\">\n\
+            ##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">\n\
+            ##contig=<ID=1,length=1000>\n\
+            ##contig=<ID=2,length=1000>\n\
+            #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\ts1\ts2\n\0";
+        let mut bcf = b"BCF\x02\x02".to_vec();
+        bcf.extend(&(text.len() as u32).to_le_bytes());
+        bcf.extend(text.as_bytes());
+
+        let mut shared: Vec<u8> = Vec::new();
+        shared.extend(&1i32.to_le_bytes());
+        shared.extend(&299i32.to_le_bytes());
+        shared.extend(&2i32.to_le_bytes());
+        shared.extend(&FLOAT_MISSING.to_le_bytes());
+        shared.extend(&(3u32 << 16 | 2).to_le_bytes());
+        shared.extend(&(1u32 << 24 | 2).to_le_bytes());
+        shared.extend(chars(""));
+        for allele in &["TA", "T", "G"] {
+            shared.extend(chars(allele));
+        }
+        shared.extend(ints(&[0]));
+        shared.extend(ints(&[1]));
+        shared.extend(ints(&[14]));
+        shared.extend(ints(&[2]));
+        shared.extend(&[0x00]);
+        // GT of `0/1` and `1|2`.
+        let mut indiv = ints(&[3]);
+        indiv.extend(&[0x21, 2, 4, 4, 7]);
+        bcf.extend(&(shared.len() as u32).to_le_bytes());
+        bcf.extend(&(indiv.len() as u32).to_le_bytes());
+        bcf.extend(shared);
+        bcf.extend(indiv);
+
+        let (header, lines) = read(&bcf[..]).unwrap();
+        assert!(header.ends_with("s1\ts2\n"));
+        assert_eq!(
+            lines,
+            vec!["2\t300\t.\tTA\tT,G\t.\tPASS\tDP=14;DB\tGT\t0/1\t1|2"]
+        );
+        assert!(read(&b"BCF\x01\x01"[..]).is_err());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let attrs = attributes("##INFO=<ID=DP,Number=1,Description=\"a, b\",IDX=3>");
+        assert_eq!(attrs.get("Description"), Some(&"\"a, b\""));
+        assert_eq!(attrs.get("IDX"), Some(&"3"));
+    }
+}
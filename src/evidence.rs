@@ -0,0 +1,174 @@
+use bam::record::Record as BamRecord;
+use serde::Serialize;
+
+use crate::filter::Reason;
+use crate::record::ReadSupport;
+use crate::seq::{Base, Support};
+use crate::summary::Category;
+
+/// Columns of per-read evidence table, in the same order with `Evidence::values`.
+pub const EVIDENCE_COLUMNS: [&str; 15] = [
+    "name",
+    "flag",
+    "mapq",
+    "strand",
+    "mate",
+    "read_pos",
+    "front_distance",
+    "end_distance",
+    "observed_ref",
+    "observed_alt",
+    "quals",
+    "margin",
+    "support",
+    "category",
+    "filter",
+];
+
+/// How a read overlapping a variant is counted, and why.
+#[derive(Serialize, Debug)]
+pub struct Evidence {
+    pub name: String,
+    pub flag: u16,
+    pub mapq: u8,
+    /// `+` or `-`.
+    pub strand: char,
+    /// 1 for read1 or unpaired read, 2 for read2.
+    pub mate: u8,
+    /// 0-based position of the first variant base in read.
+    pub read_pos: Option<u32>,
+    /// Distance of variant to read alignment start, in aligned read bases, see `ReadSupport`.
+    pub front_distance: Option<u32>,
+    /// Distance of variant to read alignment end, in aligned read bases, see `ReadSupport`.
+    pub end_distance: Option<u32>,
+    pub observed_ref: Option<String>,
+    pub observed_alt: Option<String>,
+    /// Base qualities of variant bases.
+    pub quals: Vec<u8>,
+    /// Variant within margin of read.
+    pub margin: Option<bool>,
+    /// `None` if filtered out, or read stops right before variant.
+    pub support: Option<Support>,
+    pub category: Option<Category>,
+    /// Reason of being filtered out, by flag or as partial.
+    pub filter: Option<Reason>,
+}
+
+/// Bases as a string, `-` if empty.
+fn stringify(bases: &[Base]) -> String {
+    if bases.is_empty() {
+        return String::from("-");
+    };
+    bases.iter().map(|i| i.stringify()).collect()
+}
+
+impl Evidence {
+    pub fn new(
+        record: &BamRecord,
+        read: Option<&ReadSupport>,
+        category: Option<Category>,
+        filter: Option<Reason>,
+    ) -> Self {
+        let flag = record.flag();
+        Self {
+            name: String::from_utf8_lossy(record.name()).into_owned(),
+            flag: flag.0,
+            mapq: record.mapq(),
+            strand: if flag.is_reverse_strand() { '-' } else { '+' },
+            mate: if flag.last_in_pair() { 2 } else { 1 },
+            read_pos: read.and_then(|i| i.read_pos),
+            front_distance: read.map(|i| i.distances.0),
+            end_distance: read.map(|i| i.distances.1),
+            observed_ref: read.map(|i| stringify(&i.observed_ref)),
+            observed_alt: read.map(|i| stringify(&i.observed_alt)),
+            quals: read.map_or_else(Vec::new, |i| i.quals.clone()),
+            margin: read.map(|i| i.margin),
+            support: read.map(|i| i.support),
+            category,
+            filter,
+        }
+    }
+
+    /// Values of `EVIDENCE_COLUMNS`, `.` if missing.
+    pub fn values(&self) -> Vec<String> {
+        fn or_dot<T: ToString>(v: Option<T>) -> String {
+            v.map_or_else(|| String::from("."), |i| i.to_string())
+        }
+        vec![
+            self.name.clone(),
+            self.flag.to_string(),
+            self.mapq.to_string(),
+            self.strand.to_string(),
+            self.mate.to_string(),
+            or_dot(self.read_pos),
+            or_dot(self.front_distance),
+            or_dot(self.end_distance),
+            or_dot(self.observed_ref.as_ref()),
+            or_dot(self.observed_alt.as_ref()),
+            if self.quals.is_empty() {
+                String::from(".")
+            } else {
+                self.quals
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(",")
+            },
+            or_dot(self.margin),
+            or_dot(self.support.map(|i| format!("{:?}", i))),
+            or_dot(self.category.map(|i| format!("{:?}", i))),
+            or_dot(self.filter.map(|i| format!("{:?}", i))),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::record::test::record;
+    use crate::record::VariantValidate;
+    use crate::variant::Variant;
+    use crate::Config;
+
+    #[test]
+    fn test_values() {
+        let evidence = Evidence {
+            name: String::from("dup0"),
+            flag: 1123,
+            mapq: 60,
+            strand: '+',
+            mate: 1,
+            read_pos: None,
+            front_distance: None,
+            end_distance: None,
+            observed_ref: None,
+            observed_alt: Some(stringify(&[])),
+            quals: vec![30, 40],
+            margin: None,
+            support: None,
+            category: None,
+            filter: Some(Reason::Duplicate),
+        };
+        let values = evidence.values();
+        assert_eq!(values.len(), EVIDENCE_COLUMNS.len());
+        assert_eq!(values[8..11], [".", "-", "30,40"]);
+        assert_eq!(values[14], "Duplicate");
+    }
+
+    #[test]
+    fn test_new() {
+        // Soft clipped on both sides, deletion at 105 before the variant at 107.
+        let var = Variant::try_parse("1:107C>T").unwrap();
+        let read = record(
+            "r1\t0\t1\t101\t60\t2S4M1D4M2S\t*\t0\t0\tGGAAAAATAAGG\tIIIIIIIIIIII\tMD:Z:4^A1C2",
+        );
+        let support = read.validate(&var, &Config::default()).unwrap().unwrap();
+        let evidence = Evidence::new(&read, Some(&support), Some(Category::from(&support)), None);
+        assert_eq!(evidence.read_pos, Some(7));
+        assert_eq!(evidence.front_distance, Some(6));
+        assert_eq!(evidence.end_distance, Some(3));
+        assert_eq!(evidence.observed_alt.as_deref(), Some("T"));
+        // Within the default margin of the alignment end.
+        assert_eq!(evidence.category, Some(Category::Margin));
+    }
+}
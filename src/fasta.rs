@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::sync::Mutex;
+
+use crate::alias::Aliases;
+use crate::error::err;
+use crate::seq::Base;
+use crate::variant::Variant;
+
+/// Bases fetched around a requested region, to save disk reads of neighbouring requests.
+const CACHE_FLANK: u64 = 10_000;
+
+/// FASTA index entry, a line of `.fai`.
+struct FaiEntry {
+    len: u64,
+    offset: u64,
+    line_bases: u64,
+    line_width: u64,
+}
+
+/// Cached reference sequence, (chromosome, 0-based start, bases).
+type Window = (String, u64, Vec<u8>);
+
+/// Indexed reference genome FASTA.
+///
+/// ## Warn
+///
+/// Index `.fai` is required, e.g. `samtools faidx genome.fa`.
+pub struct Fasta {
+    index: HashMap<String, FaiEntry>,
+    file: Mutex<File>,
+    cache: Mutex<Option<Window>>,
+    /// Aliases of chromosomes not found in the index.
+    aliases: Aliases,
+}
+
+impl Fasta {
+    /// Open FASTA and its index `{path}.fai`, chromosomes resolved by `aliases`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use vav::alias::Aliases;
+    /// # use vav::fasta::Fasta;
+    /// let fasta = Fasta::from_path("genome.fa", Aliases::default())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_path(path: &str, aliases: Aliases) -> Result<Self, Box<dyn Error>> {
+        let fai = format!("{}.fai", path);
+        let mut index: HashMap<String, FaiEntry> = HashMap::new();
+        for line in BufReader::new(
+            File::open(&fai)
+                .map_err(|e| err(&format!("Error opening FASTA index `{}`: {}.", fai, e)))?,
+        )
+        .lines()
+        {
+            let line = line?;
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 5 {
+                return Err(Box::new(err(&format!(
+                    "Error parsing `{}` as a FASTA index line.",
+                    line
+                ))));
+            };
+            index.insert(
+                String::from(fields[0]),
+                FaiEntry {
+                    len: fields[1].parse()?,
+                    offset: fields[2].parse()?,
+                    line_bases: fields[3].parse()?,
+                    line_width: fields[4].parse()?,
+                },
+            );
+        }
+        Ok(Self {
+            index,
+            file: Mutex::new(File::open(path)?),
+            cache: Mutex::new(None),
+            aliases,
+        })
+    }
+
+    /// Read uppercased bases of 0-based half open region `[start, end)` from disk.
+    fn read(&self, chrom: &str, start: u64, end: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+        let entry = match self.index.get(chrom) {
+            Some(v) => v,
+            None => {
+                let names: Vec<&String> = self.index.keys().collect();
+                let name = self
+                    .aliases
+                    .resolve_or_suggest(chrom, &names, "reference")?;
+                &self.index[name]
+            }
+        };
+        let end = end.min(entry.len);
+        if start >= end {
+            return Ok(Vec::new());
+        };
+        let offset = |pos: u64| {
+            entry.offset + pos / entry.line_bases * entry.line_width + pos % entry.line_bases
+        };
+        let mut raw = vec![0u8; (offset(end - 1) + 1 - offset(start)) as usize];
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| err("FASTA file lock poisoned."))?;
+        file.seek(SeekFrom::Start(offset(start)))?;
+        file.read_exact(&mut raw)?;
+        Ok(raw
+            .into_iter()
+            .filter(|i| !i.is_ascii_whitespace())
+            .map(|i| i.to_ascii_uppercase())
+            .collect())
+    }
+
+    /// Uppercased bases of 0-based half open region `[start, end)`.
+    ///
+    /// ## Warn
+    ///
+    /// Region is truncated at chromosome end.
+    pub fn fetch(&self, chrom: &str, start: u64, end: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut cache = self
+            .cache
+            .lock()
+            .map_err(|_| err("FASTA cache lock poisoned."))?;
+        let hit = match *cache {
+            Some((ref c, s, ref v)) => c == chrom && s <= start && end <= s + v.len() as u64,
+            None => false,
+        };
+        if !hit {
+            let s = start.saturating_sub(CACHE_FLANK);
+            *cache = Some((
+                String::from(chrom),
+                s,
+                self.read(chrom, s, end + CACHE_FLANK)?,
+            ));
+        };
+        match *cache {
+            Some((_, s, ref v)) => {
+                let (from, to) = ((start - s) as usize, ((end - s) as usize).min(v.len()));
+                Ok(v[from.min(to)..to].to_vec())
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Check variant ref against the reference, error if not matched.
+    ///
+    /// ## Warn
+    ///
+    /// Ref of deletion like `1:12345C>-` starts after `pos`, see `Variant::ref_start`.
+    pub fn check(&self, var: &Variant) -> Result<(), Box<dyn Error>> {
+        if var.refs().is_empty() {
+            return Ok(());
+        };
+        let start = var.ref_start() as u64;
+        let genome = self.fetch(var.chrom(), start, start + var.refs().len() as u64)?;
+        let matched = genome.len() == var.refs().len()
+            && genome
+                .iter()
+                .zip(var.refs())
+                .all(|(g, r)| Base::from_byte(*g).is_ok_and(|v| &v == r));
+        if matched {
+            Ok(())
+        } else {
+            Err(Box::new(err(&format!(
+                "Variant {} ref {} does not match reference genome {}.",
+                var,
+                var.ref_str(),
+                String::from_utf8_lossy(&genome)
+            ))))
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    /// FASTA of `text` indexed by `fai`, written in a temporary directory named after `name`.
+    ///
+    /// ## Warn
+    ///
+    /// The directory is removed once opened, reads going through the file kept open.
+    pub(crate) fn fasta(name: &str, text: &str, fai: &str) -> Fasta {
+        let dir = std::env::temp_dir().join(format!("vav-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ref.fa");
+        std::fs::write(&path, text).unwrap();
+        std::fs::write(dir.join("ref.fa.fai"), fai).unwrap();
+        let fasta = Fasta::from_path(path.to_str().unwrap(), Aliases::default()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        fasta
+    }
+
+    #[test]
+    fn test_fetch() {
+        let fasta = fasta("fasta", ">1\nACGTA\ncgtac\nGT\n", "1\t12\t3\t5\t6\n");
+        assert_eq!(fasta.fetch("1", 3, 8).unwrap(), b"TACGT".to_vec());
+        assert_eq!(fasta.fetch("1", 10, 20).unwrap(), b"GT".to_vec());
+        assert!(fasta.check(&Variant::try_parse("1:5AC>G").unwrap()).is_ok());
+        assert!(fasta.check(&Variant::try_parse("1:5A>-").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use std::num::ParseIntError;
+
+use bam::record::Flag;
+use serde::Serialize;
+
+pub const PROPER_PAIR: u16 = 0x2;
+pub const UNMAPPED: u16 = 0x4;
+pub const SECONDARY: u16 = 0x100;
+pub const QCFAIL: u16 = 0x200;
+pub const DUPLICATE: u16 = 0x400;
+pub const SUPPLEMENTARY: u16 = 0x800;
+
+/// Parse SAM flag in decimal or `0x` prefixed hexadecimal, e.g. `3844` or `0xF04`.
+pub fn parse_flag(v: &str) -> Result<u16, ParseIntError> {
+    match v.strip_prefix("0x").or_else(|| v.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => v.parse(),
+    }
+}
+
+/// Reason of a read being filtered out.
+#[derive(Serialize, PartialEq, Clone, Copy, Debug)]
+pub enum Reason {
+    Unmapped,
+    Secondary,
+    QcFail,
+    Duplicate,
+    Supplementary,
+    /// Not mapped in proper pair while required.
+    Improper,
+    /// Other flag bits required or excluded.
+    Other,
+    /// Not spanning the whole variant.
+    Partial,
+}
+
+/// Read flag filter, samtools `-f`/`-F` style.
+pub struct FlagFilter {
+    /// Bits all required.
+    include: u16,
+    /// Bits none allowed.
+    exclude: u16,
+}
+
+impl FlagFilter {
+    pub fn new(include: u16, exclude: u16) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Reason of filtering out a read of `flag`, `None` if passed.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use bam::record::Flag;
+    /// # use vav::filter::{FlagFilter, Reason, DUPLICATE};
+    /// let filter = FlagFilter::new(0, DUPLICATE);
+    /// assert_eq!(filter.reason(&Flag(1024 + 99)), Some(Reason::Duplicate));
+    /// ```
+    pub fn reason(&self, flag: &Flag) -> Option<Reason> {
+        let excluded = flag.0 & self.exclude;
+        let missing = !flag.0 & self.include;
+        if excluded & UNMAPPED != 0 {
+            Some(Reason::Unmapped)
+        } else if excluded & SECONDARY != 0 {
+            Some(Reason::Secondary)
+        } else if excluded & SUPPLEMENTARY != 0 {
+            Some(Reason::Supplementary)
+        } else if excluded & QCFAIL != 0 {
+            Some(Reason::QcFail)
+        } else if excluded & DUPLICATE != 0 {
+            Some(Reason::Duplicate)
+        } else if missing & PROPER_PAIR != 0 {
+            Some(Reason::Improper)
+        } else if excluded != 0 || missing != 0 {
+            Some(Reason::Other)
+        } else {
+            None
+        }
+    }
+}
+
+/// Number of reads filtered out, by reason.
+#[derive(Serialize, Default)]
+pub struct Filtered {
+    pub unmapped: u32,
+    pub secondary: u32,
+    pub supplementary: u32,
+    pub qcfail: u32,
+    pub duplicate: u32,
+    pub improper: u32,
+    pub other: u32,
+    pub partial: u32,
+}
+
+impl Filtered {
+    pub fn add(&mut self, reason: &Reason) {
+        match reason {
+            Reason::Unmapped => self.unmapped += 1,
+            Reason::Secondary => self.secondary += 1,
+            Reason::Supplementary => self.supplementary += 1,
+            Reason::QcFail => self.qcfail += 1,
+            Reason::Duplicate => self.duplicate += 1,
+            Reason::Improper => self.improper += 1,
+            Reason::Other => self.other += 1,
+            Reason::Partial => self.partial += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.unmapped
+            + self.secondary
+            + self.supplementary
+            + self.qcfail
+            + self.duplicate
+            + self.improper
+            + self.other
+            + self.partial
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reason() {
+        let filter = FlagFilter::new(PROPER_PAIR, 0xF04);
+        assert_eq!(filter.reason(&Flag(99)), None);
+        assert_eq!(filter.reason(&Flag(1024 + 99)), Some(Reason::Duplicate));
+        assert_eq!(filter.reason(&Flag(97)), Some(Reason::Improper));
+        assert_eq!(parse_flag("0xF04"), Ok(3844));
+    }
+}
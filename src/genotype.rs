@@ -0,0 +1,184 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::summary::Allele;
+
+/// Genotypes of a bi-allelic site, in the VCF order of PL.
+const GENOTYPES: [&str; 3] = ["0/0", "0/1", "1/1"];
+
+/// Base quality of reads without qualities, e.g. `*` in SAM.
+const DEFAULT_BASEQ: u8 = 30;
+
+/// Maximum base quality taken, as base qualities beyond are rarely calibrated.
+const MAX_BASEQ: u8 = 40;
+
+/// Maximum genotype quality, as in GATK.
+const MAX_GQ: u32 = 99;
+
+/// Genotype likelihoods of ref and alt supporting reads.
+#[derive(Default)]
+pub struct Likelihoods {
+    /// log10 likelihoods of `GENOTYPES`.
+    log10: [f64; 3],
+    /// Names of reads added, mates of a read pair added once.
+    templates: HashSet<Vec<u8>>,
+}
+
+impl Likelihoods {
+    /// Add a read named `name` supporting `allele`, by base qualities of its variant bases.
+    ///
+    /// ## Note
+    ///
+    /// - The lowest base quality, capped at `MAX_BASEQ`, is taken as the read error rate.
+    /// - Reads of other alleles are ignored, and so is the latter mate of a read pair.
+    pub fn add(&mut self, name: &[u8], allele: Allele, quals: &[u8]) {
+        let qual = quals
+            .iter()
+            .min()
+            .map_or(DEFAULT_BASEQ, |v| (*v).min(MAX_BASEQ));
+        let error = 10f64.powf(-(qual as f64) / 10.0);
+        let (from_ref, from_alt) = match allele {
+            Allele::Reference => (1.0 - error, error / 3.0),
+            Allele::Alt => (error / 3.0, 1.0 - error),
+            _ => return,
+        };
+        if !self.templates.insert(name.to_vec()) {
+            return;
+        };
+        for (dosage, v) in self.log10.iter_mut().enumerate() {
+            let alt = dosage as f64 / 2.0;
+            *v += ((1.0 - alt) * from_ref + alt * from_alt).log10();
+        }
+    }
+
+    /// Most likely genotype, `None` if no reads.
+    pub fn call(&self) -> Option<Genotype> {
+        if self.templates.is_empty() {
+            return None;
+        };
+        let max = self.log10.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mut pl = [0u32; 3];
+        for (i, v) in self.log10.iter().enumerate() {
+            pl[i] = (-10.0 * (v - max)).round() as u32;
+        }
+        let best = (0..3).min_by_key(|i| pl[*i]).unwrap_or(0);
+        let gq = (0..3)
+            .filter(|i| *i != best)
+            .map(|i| pl[i])
+            .min()
+            .unwrap_or(0)
+            .min(MAX_GQ);
+        Some(Genotype {
+            gt: String::from(GENOTYPES[best]),
+            gq,
+            pl,
+            input: None,
+            concordant: None,
+        })
+    }
+}
+
+/// Genotype called from reads.
+#[derive(Serialize, Debug)]
+pub struct Genotype {
+    /// Genotype, one of `0/0`, `0/1` and `1/1`.
+    pub gt: String,
+    /// Phred scaled genotype quality, capped at 99.
+    pub gq: u32,
+    /// Phred scaled likelihoods of `0/0`, `0/1` and `1/1`, normalized to the most likely.
+    pub pl: [u32; 3],
+    /// Input genotype, e.g. `GT` of input VCF.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    /// Whether input genotype agrees with the genotype called, by alt allele count.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concordant: Option<bool>,
+}
+
+impl Genotype {
+    /// Number of alt alleles.
+    pub fn dosage(&self) -> usize {
+        GENOTYPES.iter().position(|i| *i == self.gt).unwrap_or(0)
+    }
+
+    /// Compare to input genotype, `allele` the 1-based ALT index validated.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use vav::genotype::Likelihoods;
+    /// # use vav::summary::Allele;
+    /// # let mut likelihoods = Likelihoods::default();
+    /// # for i in 0..10 {
+    /// #     likelihoods.add(format!("r{}", i).as_bytes(), Allele::Reference, &[30]);
+    /// #     likelihoods.add(format!("a{}", i).as_bytes(), Allele::Alt, &[30]);
+    /// # }
+    /// # let mut genotype = likelihoods.call().unwrap();
+    /// // The second ALT of `1/2`, taken as `0/1`.
+    /// genotype.compare("1/2", 2);
+    /// # assert_eq!(genotype.concordant, Some(true));
+    /// ```
+    pub fn compare(&mut self, input: &str, allele: usize) {
+        self.concordant = dosage(input, allele).map(|i| i == self.dosage());
+        self.input = Some(String::from(input));
+    }
+}
+
+/// Number of `allele` (1-based ALT index) in a VCF genotype, e.g. `0|1`.
+///
+/// `None` if any allele missing, e.g. `./.`.
+pub fn dosage(gt: &str, allele: usize) -> Option<usize> {
+    let mut r = 0;
+    for i in gt.split(&['/', '|'][..]) {
+        if i.parse::<usize>().ok()? == allele {
+            r += 1;
+        };
+    }
+    Some(r)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_call() {
+        let mut v = Likelihoods::default();
+        assert!(v.call().is_none());
+        for i in 0..10 {
+            v.add(format!("r{}", i).as_bytes(), Allele::Reference, &[30, 35]);
+            v.add(format!("a{}", i).as_bytes(), Allele::Alt, &[30]);
+        }
+        v.add(b"o", Allele::Other, &[]);
+        let genotype = v.call().unwrap();
+        assert_eq!(genotype.gt, "0/1");
+        assert_eq!(genotype.gq, 99);
+        assert_eq!(genotype.pl[1], 0);
+
+        let mut v = Likelihoods::default();
+        v.add(b"a1", Allele::Alt, &[20]);
+        v.add(b"a2", Allele::Alt, &[20]);
+        // Mate of a read pair added.
+        v.add(b"a2", Allele::Alt, &[20]);
+        let genotype = v.call().unwrap();
+        assert_eq!((genotype.gt.as_str(), genotype.gq), ("1/1", 6));
+    }
+
+    #[test]
+    fn test_add_quals() {
+        let mut capped = Likelihoods::default();
+        capped.add(b"a1", Allele::Alt, &[60]);
+        let mut v = Likelihoods::default();
+        v.add(b"a1", Allele::Alt, &[MAX_BASEQ]);
+        assert_eq!(capped.log10, v.log10);
+    }
+
+    #[test]
+    fn test_dosage() {
+        assert_eq!(dosage("0/1", 1), Some(1));
+        assert_eq!(dosage("1|2", 2), Some(1));
+        assert_eq!(dosage("2/2", 1), Some(0));
+        assert_eq!(dosage("./.", 1), None);
+    }
+}
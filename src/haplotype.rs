@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::seq::{Base, Entry};
+use crate::summary::Allele;
+use crate::variant::Variant;
+
+/// Variant with shared bases trimmed.
+///
+/// `(start, end, alts)`, reference `[start, end)` (0-based) replaced by `alts`.
+fn trim(var: &Variant) -> (u32, u32, Vec<u8>) {
+    let mut refs: &[Base] = var.refs();
+    let mut alts: &[Base] = var.alts();
+    let mut start = var.ref_start();
+    while let (Some(a), Some(b)) = (refs.first(), alts.first()) {
+        if a != b {
+            break;
+        };
+        refs = &refs[1..];
+        alts = &alts[1..];
+        start += 1;
+    }
+    while let (Some(a), Some(b)) = (refs.last(), alts.last()) {
+        if a != b {
+            break;
+        };
+        refs = &refs[..refs.len() - 1];
+        alts = &alts[..alts.len() - 1];
+    }
+    (
+        start,
+        start + refs.len() as u32,
+        alts.iter().map(|i| i.to_byte()).collect(),
+    )
+}
+
+/// Allele of a read by comparing its local haplotype across the variant span to ref and alt.
+///
+/// Span of an indel covers its tandem repeat, since aligners may place an indel anywhere
+/// within, e.g. deletion of an `A` in `AAAA`, reads of the same haplotype are equivalent
+/// wherever the indel placed. Span of an MNV or delins, e.g. `1:100ACGT>TG`, covers its
+/// replaced bases.
+///
+/// ## Warn
+///
+/// - `None` for SNVs, for variant ref not in accord with read ref, and for reads not spanning
+///   the variant and its flanking bases.
+/// - `None` for indels if read haplotype neither ref nor alt, `Allele::Other` for others.
+///
+/// ## Note
+///
+/// Shift, in reference bases, of where the read places an alt indel from the variant, 0 for
+/// others, e.g. `3` for a read placing the deletion of `1:2CA>C` at the right end of `AAAA`.
+pub fn haplotype_allele(entries: &[Entry], var: &Variant) -> Option<(Allele, i64)> {
+    if var.refs().len() == 1 && var.alts().len() == 1 {
+        return None;
+    };
+    let genome: HashMap<u32, u8> = entries
+        .iter()
+        .filter_map(|i| Some((i.ref_pos()?, i.ref_nt()?.to_ascii_uppercase())))
+        .collect();
+    let at = |pos: u32| genome.get(&pos).copied();
+    // Variant ref not in accord with read ref, left to the caller.
+    for (i, base) in var.refs().iter().enumerate() {
+        if at(var.ref_start() + i as u32)? != base.to_byte() {
+            return None;
+        };
+    }
+    let (start, end, alts) = trim(var);
+    let indel = start == end || alts.is_empty();
+
+    // Shift indel to the right and to the left as far as the haplotype unchanged.
+    let (mut right, mut left) = (0u32, 0u32);
+    if alts.is_empty() {
+        while at(end + right)? == at(start + right)? {
+            right += 1;
+        }
+        while start > left && at(start - left - 1)? == at(end - left - 1)? {
+            left += 1;
+        }
+    } else if start == end {
+        let n = alts.len();
+        while at(start + right)? == alts[right as usize % n] {
+            right += 1;
+        }
+        while start > left && at(start - left - 1)? == alts[n - 1 - left as usize % n] {
+            left += 1;
+        }
+    };
+    // Span, with a flanking base on each side.
+    let (from, to) = ((start - left).checked_sub(1)?, end + right + 1);
+
+    let mut reference: Vec<u8> = Vec::with_capacity((to - from) as usize);
+    for pos in from + 1..to - 1 {
+        reference.push(at(pos)?);
+    }
+    let mut alt: Vec<u8> = reference[..(start - from - 1) as usize].to_vec();
+    alt.extend(&alts);
+    alt.extend(&reference[(end - from - 1) as usize..]);
+
+    // Read bases between the flanking bases, flanking bases required aligned.
+    let mut read: Vec<u8> = Vec::with_capacity(reference.len());
+    let mut flanks = 0;
+    let mut last: Option<u32> = None;
+    // Reference position of the first indel base of the read, inserted bases after `last`.
+    let mut placed: Option<u32> = None;
+    for entry in entries {
+        let within = match entry.ref_pos() {
+            Some(v) => {
+                last = Some(v);
+                if (v == from || v + 1 == to) && entry.record_nt().is_some() {
+                    flanks += 1;
+                };
+                from < v && v + 1 < to
+            }
+            // Insertion before the last flanking base, soft clips excluded.
+            None => last.is_some_and(|v| from <= v && v + 1 < to),
+        };
+        if let (true, Some(nt)) = (within, entry.record_nt()) {
+            read.push(nt);
+        };
+        if within && placed.is_none() && (entry.is_insertion() || entry.is_deletion()) {
+            placed = entry.ref_pos().or_else(|| last.map(|v| v + 1));
+        };
+    }
+    if last? + 1 < to {
+        return None;
+    };
+
+    if flanks == 2 && read.eq_ignore_ascii_case(&alt) {
+        let shift = match placed {
+            Some(v) if indel => v as i64 - start as i64,
+            _ => 0,
+        };
+        Some((Allele::Alt, shift))
+    } else if flanks == 2 && read.eq_ignore_ascii_case(&reference) {
+        Some((Allele::Reference, 0))
+    } else if indel {
+        None
+    } else {
+        Some((Allele::Other, 0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Entries of a read aligned from 0-based `start` against `genome`, by CIGAR like ops.
+    fn align(genome: &[u8], start: u32, read: &[u8], ops: &[(char, usize)]) -> Vec<Entry> {
+        let (mut r, mut q) = (start, 0u32);
+        let mut v = Vec::new();
+        for (op, len) in ops {
+            for _ in 0..*len {
+                match op {
+                    'M' => {
+                        v.push(Entry::new(
+                            Some(q),
+                            Some(read[q as usize]),
+                            Some(r),
+                            Some(genome[r as usize]),
+                        ));
+                        q += 1;
+                        r += 1;
+                    }
+                    'I' => {
+                        v.push(Entry::new(Some(q), Some(read[q as usize]), None, None));
+                        q += 1;
+                    }
+                    _ => {
+                        v.push(Entry::new(None, None, Some(r), Some(genome[r as usize])));
+                        r += 1;
+                    }
+                }
+            }
+        }
+        v
+    }
+
+    #[test]
+    fn test_haplotype_allele() {
+        let genome = b"GCAAAATCACACG";
+        let var = Variant::try_parse("1:2CA>C").unwrap();
+        // Deletion placed at the right end of `AAAA`.
+        let shifted = align(genome, 0, b"GCAAATCACACG", &[('M', 5), ('D', 1), ('M', 7)]);
+        assert_eq!(haplotype_allele(&shifted, &var), Some((Allele::Alt, 3)));
+        let reference = align(genome, 0, genome, &[('M', 13)]);
+        assert_eq!(
+            haplotype_allele(&reference, &var),
+            Some((Allele::Reference, 0))
+        );
+        // Not spanning the repeat.
+        let short = align(genome, 2, b"AAATC", &[('M', 5)]);
+        assert_eq!(haplotype_allele(&short, &var), None);
+
+        let var = Variant::try_parse("1:7T>TCA").unwrap();
+        let inserted = b"GCAAAATCACACACG";
+        let shifted = align(genome, 0, inserted, &[('M', 12), ('I', 2), ('M', 1)]);
+        assert_eq!(haplotype_allele(&shifted, &var), Some((Allele::Alt, 5)));
+    }
+
+    #[test]
+    fn test_haplotype_allele_mnv() {
+        let genome = b"GCAAAATCACACG";
+        let var = Variant::try_parse("1:8CA>TT").unwrap();
+        let alt = align(genome, 0, b"GCAAAATTTCACG", &[('M', 13)]);
+        assert_eq!(haplotype_allele(&alt, &var), Some((Allele::Alt, 0)));
+        let reference = align(genome, 0, genome, &[('M', 13)]);
+        assert_eq!(
+            haplotype_allele(&reference, &var),
+            Some((Allele::Reference, 0))
+        );
+        // Only the first base changed.
+        let other = align(genome, 0, b"GCAAAATTACACG", &[('M', 13)]);
+        assert_eq!(haplotype_allele(&other, &var), Some((Allele::Other, 0)));
+        // Alt at the read end, flanking base not covered.
+        let short = align(genome, 0, b"GCAAAATTT", &[('M', 9)]);
+        assert_eq!(haplotype_allele(&short, &var), None);
+    }
+
+    #[test]
+    fn test_haplotype_allele_delins() {
+        let genome = b"GCAAAATCACACG";
+        // `AATC` replaced by `TG`.
+        let var = Variant::try_parse("1:5AATC>TG").unwrap();
+        let alt = align(genome, 0, b"GCAATGACACG", &[('M', 6), ('D', 2), ('M', 5)]);
+        assert_eq!(haplotype_allele(&alt, &var), Some((Allele::Alt, 0)));
+        // Same haplotype, aligned differently.
+        let alt = align(
+            genome,
+            0,
+            b"GCAATGACACG",
+            &[('M', 4), ('D', 2), ('M', 2), ('M', 5)],
+        );
+        assert_eq!(haplotype_allele(&alt, &var), Some((Allele::Alt, 0)));
+        let other = align(genome, 0, b"GCAATACACG", &[('M', 5), ('D', 3), ('M', 5)]);
+        assert_eq!(haplotype_allele(&other, &var), Some((Allele::Other, 0)));
+    }
+}
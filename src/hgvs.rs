@@ -0,0 +1,161 @@
+use std::error::Error;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::error::{err, opterr};
+use crate::fasta::Fasta;
+use crate::seq::Base;
+use crate::variant::Variant;
+
+static HGVSEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?P<chrom>[\w\.-]+):g\.(?P<start>\d+)(?:_(?P<end>\d+))?(?:(?P<sub>[ATCGN])>(?P<subalt>[ATCGN])|del(?P<del>[ATCGN]*)ins(?P<delins>[ATCGN]+)|del(?P<deleted>[ATCGN]*)|ins(?P<ins>[ATCGN]+)|dup(?P<dup>[ATCGN]*))$").unwrap()
+});
+
+/// Whether a variant string is in HGVS genomic notation, e.g. `chr1:g.123_125del`.
+pub fn is_hgvs(v: &str) -> bool {
+    v.contains(":g.")
+}
+
+/// Parse variant from HGVS genomic notation.
+///
+/// ## Format
+///
+/// > NC_000002.12:g.29474101C>A
+/// > chr1:g.123_125del
+/// > chr1:g.100_101insTT
+/// > chr1:g.123dup
+/// > chr1:g.123_125delinsTG
+///
+/// ## Examples
+///
+/// ```rust
+/// # use vav::hgvs;
+/// let var = hgvs::try_parse("chr1:g.123_125delACG", None)?;
+/// assert_eq!(var.to_string(), "chr1:122ACG>-");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// ## Warn
+///
+/// - Deletions, insertions and duplications are parsed as abbreviated indels, e.g.
+///   `chr1:g.100_101insTT` as `chr1:100->TT`.
+/// - Reference genome required for deleted or duplicated bases not given, e.g. `chr1:g.123del`.
+/// - RefSeq accessions are kept as chromosome names, resolved by `Aliases` later.
+pub fn try_parse(v: &str, fasta: Option<&Fasta>) -> Result<Variant, Box<dyn Error>> {
+    let c = HGVSEX.captures(v).ok_or_else(|| {
+        err(&format!(
+            "Error parsing `{}` as a HGVS genomic variant, e.g. `chr1:g.123_125del`.",
+            v
+        ))
+    })?;
+    let chrom = c.name("chrom").ok_or_else(opterr)?.as_str();
+    let start: u32 = c.name("start").ok_or_else(opterr)?.as_str().parse()?;
+    let end: u32 = match c.name("end") {
+        Some(v) => v.as_str().parse()?,
+        None => start,
+    };
+    if start == 0 || end < start {
+        return Err(Box::new(err(&format!(
+            "Invalid HGVS variant `{}` position {}_{}.",
+            v, start, end
+        ))));
+    };
+    let seq = |name: &str| -> Result<Vec<Base>, Box<dyn Error>> {
+        Ok(Base::try_parse(c.name(name).ok_or_else(opterr)?.as_str())?)
+    };
+
+    if c.name("sub").is_some() {
+        if start != end {
+            return Err(Box::new(err(&format!(
+                "HGVS substitution `{}` of more than one base, use delins instead.",
+                v
+            ))));
+        };
+        Ok(Variant::new(chrom, start, seq("sub")?, seq("subalt")?))
+    } else if c.name("delins").is_some() {
+        Ok(Variant::new(
+            chrom,
+            start,
+            spanned(&c, "del", chrom, start, end, fasta)?,
+            seq("delins")?,
+        ))
+    } else if c.name("deleted").is_some() {
+        Ok(Variant::new(
+            chrom,
+            start - 1,
+            spanned(&c, "deleted", chrom, start, end, fasta)?,
+            Vec::new(),
+        ))
+    } else if c.name("ins").is_some() {
+        if end != start + 1 {
+            return Err(Box::new(err(&format!(
+                "HGVS insertion `{}` not between two adjacent positions.",
+                v
+            ))));
+        };
+        Ok(Variant::new(chrom, start, Vec::new(), seq("ins")?))
+    } else {
+        Ok(Variant::new(
+            chrom,
+            end,
+            Vec::new(),
+            spanned(&c, "dup", chrom, start, end, fasta)?,
+        ))
+    }
+}
+
+/// Bases of `[start, end]` (1-based), given by capture group `name` or from reference genome.
+fn spanned(
+    c: &Captures,
+    name: &str,
+    chrom: &str,
+    start: u32,
+    end: u32,
+    fasta: Option<&Fasta>,
+) -> Result<Vec<Base>, Box<dyn Error>> {
+    let len = (end - start + 1) as usize;
+    let bases = match (c.name(name).map(|i| i.as_str()), fasta) {
+        (Some(v), _) if !v.is_empty() => Base::try_parse(v)?,
+        (_, Some(fasta)) => fasta
+            .fetch(chrom, start as u64 - 1, end as u64)?
+            .into_iter()
+            .map(Base::from_byte)
+            .collect::<Result<Vec<Base>, _>>()?,
+        _ => {
+            return Err(Box::new(err(&format!(
+                "Reference genome required for bases of HGVS variant `{}`.",
+                c.get(0).ok_or_else(opterr)?.as_str()
+            ))))
+        }
+    };
+    if bases.len() != len {
+        return Err(Box::new(err(&format!(
+            "HGVS variant `{}` bases not of length {}.",
+            c.get(0).ok_or_else(opterr)?.as_str(),
+            len
+        ))));
+    };
+    Ok(bases)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_try_parse() {
+        let parse = |v: &str| try_parse(v, None).map(|i| i.to_string());
+        assert_eq!(parse("chr2:g.29474101C>A").unwrap(), "chr2:29474101C>A");
+        assert_eq!(parse("chr1:g.123_125delACG").unwrap(), "chr1:122ACG>-");
+        assert_eq!(parse("chr1:g.100_101insTT").unwrap(), "chr1:100->TT");
+        assert_eq!(parse("chr1:g.123_124dupAC").unwrap(), "chr1:124->AC");
+        assert_eq!(
+            parse("chr1:g.123_125delACGinsTG").unwrap(),
+            "chr1:123ACG>TG"
+        );
+        assert!(parse("chr1:g.123_125del").is_err());
+        assert!(parse("chr1:g.123_125delAC").is_err());
+        assert!(parse("chr1:g.100_102insTT").is_err());
+    }
+}
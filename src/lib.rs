@@ -0,0 +1,50 @@
+//! Validate SNP/InDel against reads of bam files, by CIGAR and MD tag.
+//!
+//! ## Examples
+//!
+//! ```rust,no_run
+//! use vav::{Config, Validator};
+//!
+//! let validator = Validator::new(Config {
+//!     mapq: 20,
+//!     ..Config::default()
+//! });
+//! let target = validator.target("2:29474101C>A")?;
+//! let varsum = validator.validate("tests/many_variants.bam", &[target])?;
+//! println!("{}", serde_json::to_string_pretty(&varsum)?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+extern crate bam;
+extern crate log;
+extern crate once_cell;
+extern crate regex;
+extern crate serde;
+
+pub mod alias;
+pub mod bamout;
+mod bcf;
+pub mod error;
+pub mod evidence;
+pub mod fasta;
+pub mod filter;
+pub mod genotype;
+mod haplotype;
+pub mod hgvs;
+pub mod record;
+pub mod sample;
+pub mod seq;
+pub mod somatic;
+pub mod stats;
+pub mod strand;
+pub mod summary;
+pub mod table;
+pub mod umi;
+mod validator;
+pub mod variant;
+pub mod vcf;
+pub mod view;
+
+pub use crate::seq::{Base, Support};
+pub use crate::summary::Summary;
+pub use crate::validator::{Config, Target, Validator};
+pub use crate::variant::Variant;
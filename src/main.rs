@@ -18,320 +18,646 @@
 //!     --bam tests/W080446T.many_variants.bam \
 //!     --var "2:29474101C>A"
 //! ```
-extern crate bam;
 #[macro_use]
 extern crate clap;
 extern crate env_logger;
 extern crate log;
-extern crate once_cell;
-extern crate regex;
 extern crate serde;
 extern crate serde_json;
+extern crate vav;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 
-use bam::bam_reader::{ModificationTime, Region};
-use bam::header::Header as BamHeader;
-use bam::record::AlignmentEntry;
-use bam::record::Record as BamRecord;
-use bam::IndexedReader as BamReader;
-use clap::Clap;
-use once_cell::sync::OnceCell;
+use clap::{AppSettings, Clap};
 use serde::Serialize;
 
-mod error;
-mod seq;
-mod variant;
-
-use crate::error::opterr;
-use crate::seq::{Base, Ordering};
-use crate::variant::Variant;
-
-static MAPQ: OnceCell<u8> = OnceCell::new();
-static MARGIN: OnceCell<u32> = OnceCell::new();
-
-trait MakeRegion {
-    fn make_region(&self, header: &BamHeader) -> Result<Region, Box<dyn Error>>;
+use vav::alias::Aliases;
+use vav::bamout::BamOut;
+use vav::error::{err, opterr};
+use vav::evidence::{Evidence, EVIDENCE_COLUMNS};
+use vav::fasta::Fasta;
+use vav::filter::{
+    parse_flag, FlagFilter, DUPLICATE, PROPER_PAIR, QCFAIL, SECONDARY, SUPPLEMENTARY,
+};
+use vav::sample::{self, Sample};
+use vav::somatic::{Paired, SomaticOptions, PAIRED_INFO};
+use vav::summary::{Allele, GENOTYPE_FORMAT, STRAND_COLUMNS, VCF_INFO};
+use vav::table::Table;
+use vav::umi::UmiOptions;
+use vav::vcf::{Vcf, VcfRecord};
+use vav::{Config, Summary, Target, Validator, Variant};
+
+/// Mark VCF as annotated by vav, with version and command line.
+fn mark(vcf: &mut Vcf) {
+    vcf.push_meta(format!("##vavVersion={}", crate_version!()));
+    vcf.push_meta(format!(
+        "##vavCommand={}",
+        std::env::args().collect::<Vec<String>>().join(" ")
+    ));
 }
 
-impl MakeRegion for Variant {
-    fn make_region(&self, header: &BamHeader) -> Result<Region, Box<dyn Error>> {
-        let rid = header.reference_id(self.chrom()).ok_or_else(opterr)?;
-        Ok(Region::new(rid, self.pos(), self.end()))
+/// `n` values of a VCF record, one for each ALT, by `lookup` of the ALT variant.
+///
+/// ## Warn
+///
+/// ALT not validated, e.g. symbolic allele `<DEL>`, has missing value `.`.
+fn allele_values<F>(record: &VcfRecord, n: usize, lookup: F) -> Result<Vec<String>, Box<dyn Error>>
+where
+    F: Fn(&str) -> Option<Vec<String>>,
+{
+    let mut values: Vec<Vec<String>> = vec![Vec::new(); n];
+    for variant in record.variants()? {
+        match variant.and_then(|v| lookup(&v.to_string())) {
+            Some(v) => {
+                for (i, value) in v.into_iter().enumerate() {
+                    values[i].push(value);
+                }
+            }
+            None => values.iter_mut().for_each(|i| i.push(String::from("."))),
+        }
     }
+    Ok(values.into_iter().map(|i| i.join(",")).collect())
 }
 
-#[derive(Serialize, Default)]
-struct Summary {
-    /// Ref support.
-    reference: u32,
-    /// Alt proper support.
-    proper: u32,
-    /// Alt support in margin.
-    margin: u32,
-    /// Alt support of low mapq.
-    lowq: u32,
-    /// Alt support of excessive support.
-    /// Example, expecting chr1:12345A>C, got chr1:12345AT>CG, chr1:12345AT>C, etc.
-    excessive: u32,
-    /// Other alleles support.
-    alleles: u32,
-    /// Unknown support or exception.
-    unknown: u32,
+/// Values of a VCF record for each of `VCF_INFO`, one for each ALT.
+fn summary_values(
+    record: &VcfRecord,
+    varsum: &HashMap<String, Summary>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    allele_values(record, VCF_INFO.len(), |v| {
+        varsum.get(v).map(|i| i.info_values().to_vec())
+    })
 }
 
-impl Summary {
-    fn total_count(&self) -> u32 {
-        self.reference
-            + self.proper
-            + self.margin
-            + self.lowq
-            + self.excessive
-            + self.alleles
-            + self.unknown
+/// Column of a sample in VCF, or the only column if validating a single sample.
+fn sample_column(vcf: &Vcf, name: &str, single: bool) -> Option<usize> {
+    let samples = vcf.samples();
+    match samples.iter().position(|i| *i == name) {
+        Some(v) => Some(v),
+        None if single && samples.len() == 1 => Some(0),
+        None => None,
     }
+}
 
-    fn alt_count(&self) -> u32 {
-        self.proper + self.margin + self.lowq + self.excessive
+/// Compare input genotypes, `GT` of VCF sample columns, to genotypes called from reads.
+fn check_genotypes(vcf: &Vcf, results: &mut [SampleSummary]) -> Result<(), Box<dyn Error>> {
+    let single = results.len() == 1;
+    for (name, varsum) in results.iter_mut() {
+        let index = match sample_column(vcf, name, single) {
+            Some(v) => v,
+            None => continue,
+        };
+        for record in vcf.records() {
+            let gt = match record.format(index, "GT") {
+                Some(v) => v,
+                None => continue,
+            };
+            for (i, variant) in record.variants()?.into_iter().enumerate() {
+                let key = match variant {
+                    Some(v) => v.to_string(),
+                    None => continue,
+                };
+                if let Some(genotype) = varsum.get_mut(&key).and_then(|v| v.genotype.as_mut()) {
+                    genotype.compare(&gt, i + 1);
+                    if genotype.concordant == Some(false) {
+                        log::warn!(
+                            "Sample {} variant {} genotype {} disagrees with input genotype {}.",
+                            name,
+                            key,
+                            genotype.gt,
+                            gt
+                        );
+                    };
+                };
+            }
+        }
     }
+    Ok(())
+}
 
-    fn alt_freq(&self) -> f32 {
-        let v = self.alt_count() as f32 / self.total_count() as f32;
-        (v * 10000.0).round() / 10000.0
+/// Annotate VCF records with supports of each ALT as INFO fields, and genotypes as FORMAT fields.
+///
+/// ## Note
+///
+/// Genotypes are annotated to the sample column of the same name, or the only sample column,
+/// or a new column appended.
+fn annotate(
+    vcf: &mut Vcf,
+    name: &str,
+    varsum: &HashMap<String, Summary>,
+) -> Result<(), Box<dyn Error>> {
+    for (id, kind, description) in VCF_INFO.iter() {
+        vcf.set_info_meta(id, "A", kind, description);
     }
-
-    fn proper_freq(&self) -> f32 {
-        let v = self.proper as f32 / self.total_count() as f32;
-        (v * 10000.0).round() / 10000.0
+    mark(vcf);
+    for record in vcf.records_mut() {
+        let values = summary_values(record, varsum)?;
+        for ((id, _, _), value) in VCF_INFO.iter().zip(values) {
+            record.set_info(id, &value);
+        }
     }
+    let index = match sample_column(vcf, name, true) {
+        Some(v) => v,
+        None => vcf.sample_index(name),
+    };
+    annotate_genotypes(vcf, index, varsum)
+}
 
-    fn margin_freq(&self) -> f32 {
-        let v = self.margin as f32 / self.total_count() as f32;
-        (v * 10000.0).round() / 10000.0
+/// Annotate VCF records with genotypes of each ALT as FORMAT fields of the `index`th sample.
+fn annotate_genotypes(
+    vcf: &mut Vcf,
+    index: usize,
+    varsum: &HashMap<String, Summary>,
+) -> Result<(), Box<dyn Error>> {
+    for (id, number, kind, description) in GENOTYPE_FORMAT.iter() {
+        vcf.set_format_meta(id, number, kind, description);
     }
-
-    fn lowq_freq(&self) -> f32 {
-        let v = self.lowq as f32 / self.total_count() as f32;
-        (v * 10000.0).round() / 10000.0
+    for record in vcf.records_mut() {
+        let values = allele_values(record, GENOTYPE_FORMAT.len(), |v| {
+            varsum.get(v).map(|i| i.genotype_values().to_vec())
+        })?;
+        for ((id, _, _, _), value) in GENOTYPE_FORMAT.iter().zip(values) {
+            record.set_format(index, id, &value);
+        }
     }
+    Ok(())
+}
 
-    fn ref_count(&self) -> u32 {
-        self.reference
+/// Annotate VCF records with supports of each ALT as FORMAT fields, a sample column each sample.
+///
+/// ## Note
+///
+/// Existing sample columns of the same names are reused.
+fn annotate_samples(vcf: &mut Vcf, results: &[SampleSummary]) -> Result<(), Box<dyn Error>> {
+    for (id, kind, description) in VCF_INFO.iter() {
+        vcf.set_format_meta(id, "A", kind, description);
     }
-
-    fn ref_freq(&self) -> f32 {
-        let v = self.ref_count() as f32 / self.total_count() as f32;
-        (v * 10000.0).round() / 10000.0
+    mark(vcf);
+    for (name, varsum) in results {
+        let index = vcf.sample_index(name);
+        for record in vcf.records_mut() {
+            let values = summary_values(record, varsum)?;
+            for ((id, _, _), value) in VCF_INFO.iter().zip(values) {
+                record.set_format(index, id, &value);
+            }
+        }
+        annotate_genotypes(vcf, index, varsum)?;
     }
+    Ok(())
+}
 
-    /// Validate record supportion for variant.
-    ///
-    /// ## Examples
-    ///
-    /// ```rust
-    /// use bam::record::Record as BamRecord;
-    ///
-    /// use crate::variant::Variant;
-    /// use crate::Summary;
-    ///
-    /// let var = Variant::try_parse("chr1:123456AT>-")?;
-    /// let record = BamRecord::new();
-    /// let sum = Summary::default();
-    /// sum.valdiate(&record, &var)?;
-    /// ```
-    ///
-    /// ## Warn
-    ///
-    /// Crate `bam` bam reader reading alignemnt with 0-based position, while variant is 1-based.
-    /// So alignment `+1` or variant `-1` is necessary in some places.
-    ///
-    fn validate(&mut self, record: &BamRecord, var: &Variant) -> Result<(), Box<dyn Error>> {
-        // Unmapped read
-        if (!record.flag().is_mapped())
-            || (record.start() + 1) as u32 > var.end()
-            || (record.calculate_end() as u32) < var.pos()
-        {
-            return Ok(());
+/// Annotate VCF records with tumor/normal comparison of each ALT as INFO fields.
+fn annotate_paired(vcf: &mut Vcf, paired: &HashMap<String, Paired>) -> Result<(), Box<dyn Error>> {
+    for (id, kind, description) in PAIRED_INFO.iter() {
+        vcf.set_info_meta(id, "A", kind, description);
+    }
+    for record in vcf.records_mut() {
+        let values = allele_values(record, PAIRED_INFO.len(), |v| {
+            paired.get(v).map(|i| i.info_values().to_vec())
+        })?;
+        for ((id, _, _), value) in PAIRED_INFO.iter().zip(values) {
+            record.set_info(id, &value);
         }
-        // Record ref
-        let mut rref: Vec<Base> = Vec::with_capacity(var.refs().len());
-        // Record alt
-        let mut ralt: Vec<Base> = Vec::with_capacity(var.alts().len());
-        // Front margin and end margin
-        let mut front = 0;
-        let mut end = 0;
-        let mut iter = if let Ok(v) = record.alignment_entries() {
-            v.skip_while(|i| {
-                front += 1;
-                i.ref_pos() < Some(var.pos() - 1)
-            })
-        } else {
-            self.unknown += 1;
-            return Ok(());
-        };
-        let mut next: Option<AlignmentEntry> = if let Some(v) = iter.next() {
-            Some(v)
-        } else {
-            return Ok(());
-        };
-
-        let mut preskip = true;
+    }
+    Ok(())
+}
 
-        while let Some(curr) = next {
-            next = iter.next();
-            if let Some(ref v) = curr.record_pos() {
-                end = *v;
-            };
+/// Names of columns appended to tables, lowercase `VCF_INFO` ids and `STRAND_COLUMNS`.
+fn column_names() -> Vec<String> {
+    VCF_INFO
+        .iter()
+        .map(|(id, _, _)| id.to_lowercase())
+        .chain(STRAND_COLUMNS.iter().map(|i| String::from(*i)))
+        .collect()
+}
 
-            if preskip && var.is_abbr_deletion() {
-                preskip = false;
-                log::info!("Skipping first base due to variant deletion format like `1:12345C>-`");
-                continue;
-            };
+/// Values of `column_names` columns.
+fn column_values(sum: &Summary) -> Vec<String> {
+    sum.info_values()
+        .iter()
+        .chain(sum.strand_values().iter())
+        .cloned()
+        .collect()
+}
 
-            if curr.is_insertion() {
-                ralt.push(Base::from_byte(curr.record_nt().ok_or_else(opterr)?)?)
-            } else if curr.is_deletion() {
-                rref.push(Base::from_byte(curr.ref_nt().ok_or_else(opterr)?)?)
-            } else {
-                ralt.push(Base::from_byte(curr.record_nt().ok_or_else(opterr)?)?);
-                rref.push(Base::from_byte(curr.ref_nt().ok_or_else(opterr)?)?)
-            };
+/// Sample name and summaries of its variants, keyed by variant input form.
+type SampleSummary = (String, HashMap<String, Summary>);
+
+/// Write variant by sample matrix of supports, a row each variant and sample.
+fn write_matrix(
+    path: &str,
+    targets: &[Target],
+    results: &[SampleSummary],
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "variant\tsample\t{}", column_names().join("\t"))?;
+    for target in targets {
+        for (name, varsum) in results {
+            let sum = varsum.get(&target.key).ok_or_else(opterr)?;
+            writeln!(
+                writer,
+                "{}\t{}\t{}",
+                target.key,
+                name,
+                column_values(sum).join("\t")
+            )?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
 
-            if let Some(ref v) = next {
-                if !v.is_seq_match() {
-                    continue;
-                }
-            };
+/// Write evidence of each read, as JSON Lines if path ends with `.jsonl`, otherwise a table.
+fn write_reads(
+    path: &str,
+    targets: &[Target],
+    results: &[SampleSummary],
+) -> Result<(), Box<dyn Error>> {
+    #[derive(Serialize)]
+    struct Row<'a> {
+        sample: &'a str,
+        variant: &'a str,
+        #[serde(flatten)]
+        read: &'a Evidence,
+    }
 
-            if rref.len() >= var.refs().len() || ralt.len() >= var.alts().len() {
-                break;
-            }
-        }
-        end = record.aligned_query_end() - end;
-
-        match (var.ref_cmp(&rref), var.alt_cmp(&ralt), rref == ralt) {
-            // Record ref does not accord with variant ref.
-            (Ordering::Nul, _, _) => {
-                log::error!(
-                    "Bam record `{}` ref {:?} does not accord with variant ref {:?}.",
-                    String::from_utf8_lossy(&record.name().to_vec()),
-                    rref,
-                    var.refs()
-                );
-                self.alleles += 1;
-                Ok(())
-            }
-            // Fully supported Alt
-            (Ordering::Equ, Ordering::Equ, _) => {
-                log::debug!(
-                    "Fully supported alt by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                if Some(&record.mapq()) < MAPQ.get() {
-                    self.lowq += 1;
-                } else if Some(&front) < MARGIN.get() || Some(&end) < MARGIN.get() {
-                    self.margin += 1;
+    let jsonl = path.ends_with(".jsonl");
+    let mut writer = BufWriter::new(File::create(path)?);
+    if !jsonl {
+        writeln!(writer, "sample\tvariant\t{}", EVIDENCE_COLUMNS.join("\t"))?;
+    };
+    for target in targets {
+        for (name, varsum) in results {
+            let sum = varsum.get(&target.key).ok_or_else(opterr)?;
+            for read in &sum.reads {
+                if jsonl {
+                    let row = Row {
+                        sample: name,
+                        variant: &target.key,
+                        read,
+                    };
+                    writeln!(writer, "{}", serde_json::to_string(&row)?)?;
                 } else {
-                    self.proper += 1;
-                }
-                Ok(())
-            }
-            // Fully supported Ref
-            (Ordering::Equ, _, true) => {
-                log::debug!(
-                    "Fully supported ref by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.reference += 1;
-                Ok(())
-            }
-            // Excessively supported ref
-            // FIXME: Extra base considered the same with genome reference
-            (Ordering::Sub, _, true) => {
-                log::debug!(
-                    "Excessively supported ref by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.reference += 1;
-                Ok(())
-            }
-            // Partially supported Ref
-            (_, _, true) => {
-                log::debug!(
-                    "Partially supported ref by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.reference += 1;
-                Ok(())
-            }
-            // Partially supported Alt
-            (Ordering::Sub, Ordering::Equ, false) => {
-                log::debug!(
-                    "Partially supported alt by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.excessive += 1;
-                Ok(())
-            }
-            // Excessively supported Alt
-            (_, Ordering::Sub, false) => {
-                log::debug!(
-                    "Excessively supported alt by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.excessive += 1;
-                Ok(())
-            }
-            // Partially supported Alt
-            (_, Ordering::Sup, false) => {
-                log::debug!(
-                    "Partially supported alt (interpreted as other allele) by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.alleles += 1;
-                Ok(())
-            }
-            _ => {
-                log::debug!(
-                    "Other allele by record `{}`",
-                    String::from_utf8_lossy(&record.name().to_vec())
-                );
-                self.alleles += 1;
-                Ok(())
+                    writeln!(
+                        writer,
+                        "{}\t{}\t{}",
+                        name,
+                        target.key,
+                        read.values().join("\t")
+                    )?;
+                };
             }
         }
     }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Bam file of sample `name`, e.g. `tumor.alt.bam` of `alt.bam`, for outputs of multiple samples.
+fn sample_path(path: &str, name: &str) -> String {
+    let path = Path::new(path);
+    let file = format!(
+        "{}.{}",
+        name,
+        path.file_name()
+            .map_or_else(String::new, |i| i.to_string_lossy().into_owned())
+    );
+    path.with_file_name(file).to_string_lossy().into_owned()
+}
+
+/// Write reads of `allele` counted for variants to a sorted and indexed bam file.
+fn write_bam(
+    path: &str,
+    bam: &str,
+    allele: Allele,
+    targets: &[Target],
+    varsum: &HashMap<String, Summary>,
+) -> Result<(), Box<dyn Error>> {
+    let command = std::env::args().collect::<Vec<String>>().join(" ");
+    let mut out = BamOut::from_bam(bam, &command)?;
+    for target in targets {
+        let sum = varsum.get(&target.key).ok_or_else(opterr)?;
+        for (category, record) in &sum.records {
+            if category.allele() == allele {
+                out.add(&target.key, *category, record);
+            };
+        }
+    }
+    log::warn!("Writing {} reads to bam file {}.", out.len(), path);
+    out.write(path)
 }
 
 #[derive(Clap)]
 #[clap(name = crate_name!(), version = crate_version!(), author = crate_authors!(), about = crate_description!())]
+#[clap(setting = AppSettings::SubcommandsNegateReqs)]
 struct Opts {
+    #[clap(subcommand)]
+    command: Option<Command>,
     #[clap(
+        global = true,
         long,
         number_of_values = 1,
-        about = "Input genome variant, e.g. 'chr1:12345AT>-'."
+        about = "Input genome variant, e.g. 'chr1:12345AT>-', 'chr1-12345-AT-A', 'chr1:12345:AT:A', 'chr1 12345 . AT A', or in HGVS genomic notation, e.g. 'chr1:g.12346del'."
     )]
     var: Vec<String>,
-    #[clap(long, default_value = "30", about = "Minimum read mapping quality.")]
+    #[clap(
+        global = true,
+        long,
+        alias = "accessions",
+        about = "Tab-separated table of chromosome aliases, names on a line are equivalent, e.g. '2<TAB>chr2<TAB>NC_000002.12'. Names differing by 'chr' prefix are always equivalent. Formerly --accessions, still accepted."
+    )]
+    aliases: Option<String>,
+    #[clap(
+        long,
+        about = "Input VCF or BCF of genome variants, VCF plain or bgzipped, multi-allelic records are splitted per ALT."
+    )]
+    vcf: Option<String>,
+    #[clap(
+        long,
+        requires = "vcf",
+        about = "Output VCF annotated with supports of each ALT, bgzipped if ending with `.gz`."
+    )]
+    vcf_out: Option<String>,
+    #[clap(
+        long,
+        about = "Input tab-separated table of genome variants, e.g. 'tests/variants_table.xls'."
+    )]
+    table: Option<String>,
+    #[clap(
+        long,
+        default_value = "Chromosome,Start_position,Ref,Alt",
+        about = "Table column names of chromosome, start position, ref and alt, comma separated."
+    )]
+    table_columns: String,
+    #[clap(
+        long,
+        requires = "table",
+        about = "Output table with supports of each row appended as extra columns."
+    )]
+    table_out: Option<String>,
+    #[clap(
+        global = true,
+        short,
+        long,
+        about = "Reference genome FASTA indexed with `.fai`, to check variant ref and to compare reads without MD tag."
+    )]
+    reference: Option<String>,
+    #[clap(
+        global = true,
+        long,
+        default_value = "30",
+        about = "Minimum read mapping quality."
+    )]
     mapq: u8,
     #[clap(
+        global = true,
+        long,
+        default_value = "20",
+        about = "Minimum base quality of alt supporting bases."
+    )]
+    min_baseq: u8,
+    #[clap(
+        global = true,
         long,
         default_value = "10",
         about = "Minimum margin base distance for alt support. Margin stands for read start/end, softclip start/end etc."
     )]
     margin: u32,
-    #[clap(short, long, about = "Print verbose info.")]
+    #[clap(
+        global = true,
+        short = 'f',
+        long,
+        default_value = "0",
+        parse(try_from_str = parse_flag),
+        about = "Only count reads with all of the flag bits set, e.g. 0x2."
+    )]
+    include_flags: u16,
+    #[clap(
+        global = true,
+        short = 'F',
+        long,
+        default_value = "0xF04",
+        parse(try_from_str = parse_flag),
+        about = "Only count reads with none of the flag bits set, unmapped, secondary, qcfail, duplicate and supplementary by default."
+    )]
+    exclude_flags: u16,
+    #[clap(long, about = "Count duplicate reads, flag 0x400.")]
+    keep_duplicates: bool,
+    #[clap(long, about = "Count secondary alignments, flag 0x100.")]
+    keep_secondary: bool,
+    #[clap(long, about = "Count supplementary alignments, flag 0x800.")]
+    keep_supplementary: bool,
+    #[clap(long, about = "Count reads failing quality controls, flag 0x200.")]
+    keep_qcfail: bool,
+    #[clap(long, about = "Only count reads mapped in proper pair, flag 0x2.")]
+    proper_pairs_only: bool,
+    #[clap(
+        long,
+        about = "Count fragments besides reads, overlapping mates of a read pair counted once."
+    )]
+    fragments: bool,
+    #[clap(
+        long,
+        about = "Tag holding UMI, e.g. RX or MI, count UMI families besides reads if given."
+    )]
+    umi_tag: Option<String>,
+    #[clap(
+        long,
+        requires = "umi-tag",
+        about = "Group UMI families by fragment start position besides UMI."
+    )]
+    umi_by_position: bool,
+    #[clap(
+        long,
+        default_value = "0",
+        about = "Maximum edit distance between UMIs of a family."
+    )]
+    umi_mismatches: usize,
+    #[clap(
+        long,
+        default_value = "2",
+        about = "Minimum family size (number of templates, mates of a read pair counted once) for alt families to be reported."
+    )]
+    min_family_size: usize,
+    #[clap(
+        long,
+        about = "Output evidence of each read overlapping variants, e.g. support, category and filter reason, as JSON Lines if ending with `.jsonl`, otherwise tab-separated."
+    )]
+    reads_out: Option<String>,
+    #[clap(
+        long,
+        about = "Output reads supporting alt as a sorted and indexed bam file, each read tagged with its category, e.g. 'YV:Z:proper'. Sample names prefixed to file name if multiple samples."
+    )]
+    alt_bam: Option<String>,
+    #[clap(
+        long,
+        about = "Output reads supporting ref as a sorted and indexed bam file, like --alt-bam."
+    )]
+    ref_bam: Option<String>,
+    #[clap(
+        long,
+        about = "Output reads supporting other alleles as a sorted and indexed bam file, like --alt-bam."
+    )]
+    other_bam: Option<String>,
+    #[clap(
+        long,
+        default_value = "0.001",
+        about = "Background error rate of alt supporting reads, to test alt detection and estimate limit of detection."
+    )]
+    error_rate: f64,
+    #[clap(global = true, short, long, about = "Print verbose info.")]
     verbose: bool,
+    #[clap(
+        long,
+        about = "Tab-separated sample sheet of sample names and bam files, e.g. 'tumor<TAB>tumor.bam'."
+    )]
+    samples: Option<String>,
+    #[clap(
+        long,
+        about = "Output tab-separated variant by sample matrix of supports, a row each variant and sample."
+    )]
+    matrix_out: Option<String>,
+    #[clap(
+        long,
+        requires = "normal",
+        about = "Tumor bam file of tumor/normal paired mode, sample named 'tumor', variants classified as somatic, germline, loh or artifact."
+    )]
+    tumor: Option<String>,
+    #[clap(
+        long,
+        requires = "tumor",
+        about = "Matched normal bam file of tumor/normal paired mode, sample named 'normal'."
+    )]
+    normal: Option<String>,
+    #[clap(
+        long,
+        default_value = "0.02",
+        about = "Maximum normal alt fraction of somatic variants."
+    )]
+    max_normal_af: f64,
+    #[clap(
+        long,
+        default_value = "0.2",
+        about = "Minimum normal alt fraction of germline variants."
+    )]
+    min_germline_af: f64,
+    #[clap(
+        long,
+        default_value = "3",
+        about = "Minimum tumor reads properly supporting alt of somatic variants."
+    )]
+    min_tumor_alt: u32,
+    #[clap(
+        long,
+        default_value = "0.05",
+        about = "Maximum Fisher's exact test p-value between tumor and normal ref/alt counts of somatic and loh variants."
+    )]
+    max_paired_pvalue: f64,
+    #[clap(
+        required_unless_present_any = &["samples", "tumor"],
+        about = "Input bam files, samples named after file names."
+    )]
+    bam: Vec<String>,
+}
+
+#[derive(Clap)]
+enum Command {
+    #[clap(
+        about = "View reads around variants given by --var in terminal, grouped by category, e.g. 'vav view tumor.bam --var 1:12345A>G'."
+    )]
+    View(ViewOpts),
+}
+
+#[derive(Clap)]
+struct ViewOpts {
     #[clap(about = "Input bam file.")]
     bam: String,
+    #[clap(
+        long,
+        default_value = "80",
+        about = "Window width in reference bases, centered on variant."
+    )]
+    width: u32,
+    #[clap(
+        long,
+        default_value = "50",
+        about = "Maximum reads shown per category."
+    )]
+    max_reads: usize,
+    #[clap(long, about = "Plain text without ANSI colors.")]
+    no_color: bool,
+}
+
+/// Print stacked views of reads around variants.
+fn view(validator: &Validator, vars: &[String], opts: &ViewOpts) -> Result<(), Box<dyn Error>> {
+    if vars.is_empty() {
+        return Err(Box::new(err("No variant to view, given by --var.")));
+    };
+    for each in vars {
+        let target = validator.target(each)?;
+        let view = validator.view(&opts.bam, &target.variant, opts.width)?;
+        println!("{}", view.render(!opts.no_color, opts.max_reads));
+    }
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut opts = Opts::parse();
-    MAPQ.set(opts.mapq).map_err(|_| opterr())?;
-    MARGIN.set(opts.margin).map_err(|_| opterr())?;
+    let opts = Opts::parse();
+    let aliases = match opts.aliases {
+        Some(ref path) => Aliases::from_path(path)?,
+        None => Aliases::default(),
+    };
+    let reference = match opts.reference {
+        Some(ref path) => Some(Fasta::from_path(path, aliases.clone())?),
+        None => None,
+    };
+    let umi = match opts.umi_tag {
+        Some(ref tag) => Some(UmiOptions {
+            tag: match tag.as_bytes() {
+                [a, b] => [*a, *b],
+                _ => return Err(Box::new(err(&format!("Invalid UMI tag `{}`.", tag)))),
+            },
+            by_position: opts.umi_by_position,
+            mismatches: opts.umi_mismatches,
+            min_family_size: opts.min_family_size,
+        }),
+        None => None,
+    };
+    let mut exclude = opts.exclude_flags;
+    for (keep, bit) in [
+        (opts.keep_duplicates, DUPLICATE),
+        (opts.keep_secondary, SECONDARY),
+        (opts.keep_supplementary, SUPPLEMENTARY),
+        (opts.keep_qcfail, QCFAIL),
+    ]
+    .iter()
+    {
+        if *keep {
+            exclude &= !bit;
+        };
+    }
+    let include = if opts.proper_pairs_only {
+        opts.include_flags | PROPER_PAIR
+    } else {
+        opts.include_flags
+    };
+    let validator = Validator::new(Config {
+        mapq: opts.mapq,
+        margin: opts.margin,
+        min_baseq: opts.min_baseq,
+        filter: FlagFilter::new(include, exclude),
+        fragments: opts.fragments,
+        umi,
+        error_rate: opts.error_rate,
+        evidence: opts.reads_out.is_some(),
+        records: opts.alt_bam.is_some() || opts.ref_bam.is_some() || opts.other_bam.is_some(),
+        reference,
+        aliases,
+    });
 
     env_logger::Builder::new()
         .filter_level(if opts.verbose {
@@ -341,60 +667,226 @@ fn main() -> Result<(), Box<dyn Error>> {
         })
         .init();
 
-    log::warn!("Reading bam file {}.", &opts.bam);
-    let mut sam = BamReader::build()
-        .modification_time(ModificationTime::warn(|e| eprintln!("{}", e)))
-        .from_path(&opts.bam)?;
+    if let Some(Command::View(ref v)) = opts.command {
+        return view(&validator, &opts.var, v);
+    };
+
+    // Variants to validate, keyed by their input form.
+    let mut variants: Vec<(String, Variant)> = Vec::new();
+    for each in &opts.var {
+        variants.push((each.clone(), validator.parse(each)?));
+    }
+    let mut vcf = match opts.vcf {
+        Some(ref path) => {
+            log::warn!("Reading vcf file {}.", path);
+            Some(Vcf::from_path(path)?)
+        }
+        None => None,
+    };
+    if let Some(ref v) = vcf {
+        for record in v.records() {
+            for variant in record.variants()?.into_iter().flatten() {
+                variants.push((variant.to_string(), variant));
+            }
+        }
+    };
+    let mut table = match opts.table {
+        Some(ref path) => {
+            log::warn!("Reading table file {}.", path);
+            Some(Table::from_path(
+                path,
+                &opts.table_columns.split(',').collect::<Vec<&str>>(),
+            )?)
+        }
+        None => None,
+    };
+    if let Some(ref v) = table {
+        for variant in v.variants()? {
+            variants.push((variant.to_string(), variant));
+        }
+    };
 
-    let mut varsum: HashMap<String, Summary> = HashMap::new();
-    while let Some(each) = opts.var.pop() {
-        if varsum.contains_key(&each) {
+    let mut targets: Vec<Target> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for (each, variant) in variants {
+        if !seen.insert(each.clone()) {
             continue;
         };
-        let variant = Variant::try_parse(&each)?;
-        let mut sum = Summary::default();
-        log::warn!("Variant {} Parsed as {:?}", &each, variant);
-
-        log::warn!("Fetching variant adjcent reads.");
-        let reg = variant.make_region(sam.header())?;
-        for i in sam.fetch(&reg)? {
-            let record = i?;
-            if ((record.start() + 1) as u32 > variant.pos())
-                || ((record.calculate_end() as u32) < variant.end())
-            {
-                break;
+        targets.push(validator.target_of(each, variant)?);
+    }
+
+    let mut samples: Vec<Sample> = Vec::new();
+    if let (Some(ref tumor), Some(ref normal)) = (&opts.tumor, &opts.normal) {
+        for (name, bam) in [("tumor", tumor), ("normal", normal)].iter() {
+            samples.push(Sample {
+                name: String::from(*name),
+                bam: String::from(*bam),
+            });
+        }
+    };
+    samples.extend(opts.bam.iter().map(|i| Sample::from_bam(i)));
+    if let Some(ref path) = opts.samples {
+        samples.extend(sample::from_sheet(path)?);
+    };
+    sample::check_unique(&samples)?;
+    let mut results: Vec<SampleSummary> = Vec::with_capacity(samples.len());
+    for each in &samples {
+        log::warn!("Validating sample {}.", each.name);
+        results.push((each.name.clone(), validator.validate(&each.bam, &targets)?));
+    }
+
+    if let Some(ref v) = vcf {
+        check_genotypes(v, &mut results)?;
+    };
+
+    // Tumor/normal comparisons, keyed by variant input form.
+    let paired: Option<HashMap<String, Paired>> = match opts.tumor {
+        Some(_) => {
+            let somatic = SomaticOptions {
+                max_normal_af: opts.max_normal_af,
+                min_germline_af: opts.min_germline_af,
+                min_tumor_alt: opts.min_tumor_alt,
+                max_pvalue: opts.max_paired_pvalue,
             };
-            match sum.validate(&record, &variant) {
-                Ok(_) => {}
-                Err(e) => {
-                    log::error!("{}", e)
+            let sample = |name: &str| {
+                results
+                    .iter()
+                    .find(|(i, _)| i == name)
+                    .map(|(_, varsum)| varsum)
+                    .ok_or_else(opterr)
+            };
+            let (tumor, normal) = (sample("tumor")?, sample("normal")?);
+            let mut paired: HashMap<String, Paired> = HashMap::new();
+            for target in &targets {
+                let v = Paired::classify(
+                    tumor.get(&target.key).ok_or_else(opterr)?,
+                    normal.get(&target.key).ok_or_else(opterr)?,
+                    &somatic,
+                );
+                log::warn!(
+                    "Variant {} {}; Tumor AF {}; Normal AF {}; Tumor/normal FS {}",
+                    target.key,
+                    v.status.as_str(),
+                    v.tumor_af,
+                    v.normal_af,
+                    v.fs
+                );
+                paired.insert(target.key.clone(), v);
+            }
+            Some(paired)
+        }
+        None => None,
+    };
+
+    if let (Some(ref path), Some(ref mut v)) = (&opts.vcf_out, &mut vcf) {
+        log::warn!("Writing annotated vcf file {}.", path);
+        match &results[..] {
+            [(name, varsum)] => annotate(v, name, varsum)?,
+            _ => annotate_samples(v, &results)?,
+        };
+        if let Some(ref paired) = paired {
+            annotate_paired(v, paired)?;
+        };
+        v.write(path)?;
+    };
+
+    if let (Some(ref path), Some(ref mut v)) = (&opts.table_out, &mut table) {
+        log::warn!("Writing annotated table file {}.", path);
+        let variants = v.variants()?;
+        for (name, varsum) in &results {
+            let mut values: Vec<Vec<String>> = vec![Vec::new(); column_names().len()];
+            for variant in &variants {
+                let sum = varsum.get(&variant.to_string()).ok_or_else(opterr)?;
+                for (i, value) in column_values(sum).into_iter().enumerate() {
+                    values[i].push(value);
+                }
+            }
+            for (column, values) in column_names().iter().zip(values) {
+                if results.len() == 1 {
+                    v.push_column(column, values);
+                } else {
+                    v.push_column(&format!("{}_{}", name, column), values);
                 }
             }
         }
-
-        log::warn!(
-            "Variant {} total {}; Ref {}({}); Proper alt {}({}); Margin alt {}({}); Lowq alt {}({})",
-            &each,
-            sum.total_count(),
-            sum.reference,
-            sum.ref_freq(),
-            sum.proper,
-            sum.proper_freq(),
-            sum.margin,
-            sum.margin_freq(),
-            sum.lowq,
-            sum.lowq_freq(),
-        );
-        varsum.insert(each, sum);
+        if let Some(ref paired) = paired {
+            let mut values: Vec<Vec<String>> = vec![Vec::new(); PAIRED_INFO.len()];
+            for variant in &variants {
+                let v = paired.get(&variant.to_string()).ok_or_else(opterr)?;
+                for (i, value) in v.info_values().iter().enumerate() {
+                    values[i].push(value.clone());
+                }
+            }
+            for ((id, _, _), values) in PAIRED_INFO.iter().zip(values) {
+                v.push_column(&id.to_lowercase(), values);
+            }
+        };
+        v.write(path)?;
+    };
+
+    if let Some(ref path) = opts.matrix_out {
+        log::warn!("Writing variant by sample matrix file {}.", path);
+        write_matrix(path, &targets, &results)?;
+    };
+
+    if let Some(ref path) = opts.reads_out {
+        log::warn!("Writing read evidence file {}.", path);
+        write_reads(path, &targets, &results)?;
+    };
+
+    for (path, allele) in [
+        (&opts.alt_bam, Allele::Alt),
+        (&opts.ref_bam, Allele::Reference),
+        (&opts.other_bam, Allele::Other),
+    ]
+    .iter()
+    {
+        if let Some(path) = path {
+            for (each, (name, varsum)) in samples.iter().zip(&results) {
+                let out = if results.len() == 1 {
+                    path.clone()
+                } else {
+                    sample_path(path, name)
+                };
+                write_bam(&out, &each.bam, *allele, &targets, varsum)?;
+            }
+        };
     }
 
-    if varsum.len() == 1usize {
-        println!(
+    match &results[..] {
+        [(_, varsum)] if varsum.len() == 1usize => println!(
             "{}",
             serde_json::to_string_pretty(&varsum.values().next().ok_or_else(opterr)?)?
-        );
-    } else {
-        println!("{}", serde_json::to_string_pretty(&varsum)?);
-    }
+        ),
+        [(_, varsum)] => println!("{}", serde_json::to_string_pretty(&varsum)?),
+        _ => {
+            // Variant by sample matrix, with tumor/normal comparison as `somatic` if paired.
+            let mut matrix: HashMap<&String, HashMap<&str, serde_json::Value>> = HashMap::new();
+            for (name, varsum) in &results {
+                for (key, sum) in varsum {
+                    matrix
+                        .entry(key)
+                        .or_default()
+                        .insert(name, serde_json::to_value(sum)?);
+                }
+            }
+            if let Some(ref paired) = paired {
+                for (key, v) in paired {
+                    matrix
+                        .entry(key)
+                        .or_default()
+                        .insert("somatic", serde_json::to_value(v)?);
+                }
+            };
+            if matrix.len() == 1usize {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&matrix.values().next().ok_or_else(opterr)?)?
+                );
+            } else {
+                println!("{}", serde_json::to_string_pretty(&matrix)?);
+            }
+        }
+    };
     Ok(())
 }
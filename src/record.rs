@@ -1,32 +1,66 @@
 use std::error::Error;
 
-use bam::record::AlignmentEntry;
 use bam::record::Record as BamRecord;
 
 use crate::error::opterr;
-use crate::seq::{Base, Ordering, Support};
+use crate::fasta::Fasta;
+use crate::haplotype::haplotype_allele;
+use crate::seq::{Base, Entry, Ordering, Support};
+use crate::summary::Allele;
+use crate::validator::Config;
 use crate::variant::Variant;
 
+/// Support of a read for a variant, with annotations for alt support.
+#[derive(Debug)]
+pub struct ReadSupport {
+    pub support: Support,
+    /// Mapping quality below `Config::mapq`.
+    pub lowq: bool,
+    /// Any variant base quality below `Config::min_baseq`.
+    pub lowbq: bool,
+    /// Variant within `Config::margin` bases from read alignment start or end.
+    pub margin: bool,
+    /// 0-based position of the first variant base in read.
+    pub read_pos: Option<u32>,
+    /// Distances of variant to read alignment start and end, in aligned read bases.
+    ///
+    /// Variant bases included, soft clips and deleted bases excluded, e.g. `(1, 1)` for a
+    /// read of a single aligned base at the variant.
+    pub distances: (u32, u32),
+    /// Ref observed in read, from reference genome or MD tag.
+    pub observed_ref: Vec<Base>,
+    /// Alt observed in read.
+    pub observed_alt: Vec<Base>,
+    /// Base qualities of variant bases, empty if qualities not available.
+    pub quals: Vec<u8>,
+}
+
 /// Validate alignment supportion for variant.
 pub trait VariantValidate {
-    fn validate(&self, v: &Variant) -> Result<Support, Box<dyn Error>>;
+    fn validate(
+        &self,
+        var: &Variant,
+        config: &Config,
+    ) -> Result<Option<ReadSupport>, Box<dyn Error>>;
 }
 
 impl VariantValidate for BamRecord {
     /// Validate record supportion for variant.
     ///
+    /// `None` if record unmapped, out of variant region, or stops right before variant.
+    ///
     /// ## Examples
     ///
     /// ```rust
     /// use bam::record::Record as BamRecord;
     ///
-    /// use crate::variant::Variant;
+    /// use vav::record::VariantValidate;
+    /// use vav::{Config, Variant};
     ///
     /// let var = Variant::try_parse("chr1:123456AT>-")?;
     /// let record = BamRecord::new();
-    ///
-    /// // should raise error
-    /// record.validate(var)?;
+    /// assert!(record.validate(&var, &Config::default())?.is_none());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     ///
     /// ## Warn
@@ -34,41 +68,79 @@ impl VariantValidate for BamRecord {
     /// Crate `bam` bam reader reading alignemnt with 0-based position, while variant is 1-based.
     /// So alignment `+1` or variant `-1` is necessary in some places.
     ///
-    fn validate(&self, var: &Variant) -> Result<Support, Box<dyn Error>> {
-        // Unmapped read
+    fn validate(
+        &self,
+        var: &Variant,
+        config: &Config,
+    ) -> Result<Option<ReadSupport>, Box<dyn Error>> {
         if (!self.flag().is_mapped())
             || (self.start() + 1) as u32 > var.end()
             || (self.calculate_end() as u32) < var.pos()
         {
-            return Ok(Support::Nul);
+            return Ok(None);
         }
         // Record ref
         let mut rref: Vec<Base> = Vec::with_capacity(var.refs().len());
         // Record alt
         let mut ralt: Vec<Base> = Vec::with_capacity(var.alts().len());
-        let mut iter = if let Ok(mut v) = self.alignment_entries() {
-            v.skip_while(|i| i.ref_pos() < Some(var.pos() - 1))
+        // Record base qualities, empty if qualities not available
+        let mut quals: Vec<u8> = Vec::with_capacity(var.alts().len());
+        // Read position of the last variant base
+        let mut last: Option<u32> = None;
+        // Raw qualities are all 0xFF if not available, e.g. `*` in SAM.
+        let qualities = self.qualities();
+        let qualities = if qualities.available() {
+            qualities.raw()
+        } else {
+            &[]
+        };
+        let entries = if let Some(v) = entries(self, var, config.reference.as_ref())? {
+            v
         } else {
-            return Ok(Support::Unk);
+            return Ok(Some(ReadSupport {
+                support: Support::Unk,
+                lowq: false,
+                lowbq: false,
+                margin: false,
+                read_pos: None,
+                distances: (0, 0),
+                observed_ref: rref,
+                observed_alt: ralt,
+                quals,
+            }));
         };
-        let mut next: Option<AlignmentEntry> = if let Some(v) = iter.next() {
+        // Indels, MNVs and delins compared by read haplotype across the variant span.
+        let haplotype = haplotype_allele(&entries, var);
+        // Alt indels walked from where the read places them, e.g. elsewhere in a repeat.
+        let anchor = match haplotype {
+            Some((Allele::Alt, shift)) => (var.pos() - 1) as i64 + shift,
+            _ => (var.pos() - 1) as i64,
+        };
+        let mut iter = entries
+            .iter()
+            .skip_while(|i| i.ref_pos().map(|v| v as i64) < Some(anchor));
+        let mut next: Option<&Entry> = if let Some(v) = iter.next() {
             Some(v)
         } else {
-            return Ok(Support::Nul);
+            return Ok(None);
         };
 
         let mut preskip = true;
+        let mut read_pos: Option<u32> = None;
 
-        loop {
-            let curr = match next {
-                Some(v) => v,
-                None => break,
-            };
+        while let Some(curr) = next {
             next = iter.next();
+            if let Some(ref v) = curr.record_pos() {
+                read_pos = read_pos.or(Some(*v));
+                last = Some(*v);
+                if let Some(q) = qualities.get(*v as usize) {
+                    quals.push(*q);
+                };
+            };
 
             if preskip && var.is_abbr_deletion() {
                 preskip = false;
-                log::warn!("Skipping first base due to variant deletion format like `1:12345C>-`");
+                log::info!("Skipping first base due to variant deletion format like `1:12345C>-`");
                 continue;
             };
 
@@ -81,47 +153,164 @@ impl VariantValidate for BamRecord {
                 rref.push(Base::from_byte(curr.ref_nt().ok_or_else(opterr)?)?)
             };
 
-            match next {
-                Some(ref v) => {
-                    if !v.is_seq_match() {
-                        continue;
-                    }
+            if let Some(v) = next {
+                if !v.is_seq_match() {
+                    continue;
                 }
-                _ => {}
             };
 
             if rref.len() >= var.refs().len() || ralt.len() >= var.alts().len() {
                 break;
             }
         }
+        // Front margin and end margin, in aligned read bases, variant bases included.
+        let front = read_pos.map_or(0, |v| (v + 1).saturating_sub(self.aligned_query_start()));
+        let end = last.map_or(0, |v| self.aligned_query_end().saturating_sub(v));
 
-        match (var.ref_cmp(&rref), var.alt_cmp(&ralt), rref == ralt) {
+        let compared = match haplotype.map(|(allele, _)| allele) {
+            Some(Allele::Alt) => (Ordering::Equ, Ordering::Equ, false),
+            Some(Allele::Reference) => (Ordering::Equ, Ordering::Nul, true),
+            Some(Allele::Other) => (Ordering::Equ, Ordering::Nul, false),
+            _ => (var.ref_cmp(&rref), var.alt_cmp(&ralt), rref == ralt),
+        };
+        let support = match compared {
             // Record ref does not accord with variant ref.
             (Ordering::Nul, _, _) => {
                 log::error!(
-                    "Bam record `{:?}` ref {:?} does not accord with variant ref {:?}.",
-                    String::from_utf8(self.name().to_vec()),
+                    "Bam record `{}` ref {:?} does not accord with variant ref {:?}.",
+                    String::from_utf8_lossy(self.name()),
                     rref,
                     var.refs()
                 );
-                Ok(Support::Oth)
+                Support::Nul
             }
             // Fully supported Alt
-            (Ordering::Equ, Ordering::Equ, _) => Ok(Support::Alt),
+            (Ordering::Equ, Ordering::Equ, _) => Support::Alt,
             // Fully supported Ref
-            (Ordering::Equ, _, true) => Ok(Support::Ref),
+            (Ordering::Equ, _, true) => Support::Ref,
             // Excessively supported ref
             // FIXME: Extra base considered the same with genome reference
-            (Ordering::Sub, _, true) => Ok(Support::Ree),
+            (Ordering::Sub, _, true) => Support::Ree,
             // Partially supported Ref
-            (_, _, true) => Ok(Support::Rep),
+            (_, _, true) => Support::Rep,
             // Partially supported Alt
-            (Ordering::Sub, Ordering::Equ, false) => Ok(Support::Ale),
+            (Ordering::Sub, Ordering::Equ, false) => Support::Ale,
             // Excessively supported Alt
-            (_, Ordering::Sub, false) => Ok(Support::Ale),
+            (_, Ordering::Sub, false) => Support::Ale,
             // Partially supported Alt
-            (_, Ordering::Sup, false) => Ok(Support::Alp),
-            _ => Ok(Support::Oth),
-        }
+            (_, Ordering::Sup, false) => Support::Alp,
+            _ => Support::Oth,
+        };
+        log::debug!(
+            "Support {:?} by record `{}`",
+            support,
+            String::from_utf8_lossy(self.name())
+        );
+        Ok(Some(ReadSupport {
+            support,
+            lowq: self.mapq() < config.mapq,
+            lowbq: quals.iter().any(|q| *q < config.min_baseq),
+            margin: front < config.margin || end < config.margin,
+            read_pos,
+            distances: (front, end),
+            observed_ref: rref,
+            observed_alt: ralt,
+            quals,
+        }))
+    }
+}
+
+/// Alignment entries of a record, from its MD tag, or from reference genome if MD missing.
+///
+/// `None` if neither MD tag nor reference genome available.
+pub(crate) fn entries(
+    record: &BamRecord,
+    var: &Variant,
+    reference: Option<&Fasta>,
+) -> Result<Option<Vec<Entry>>, Box<dyn Error>> {
+    if let Ok(v) = record.alignment_entries() {
+        return Ok(Some(v.map(Entry::from).collect()));
+    };
+    let fasta = match reference {
+        Some(v) if record.sequence().available() => v,
+        _ => return Ok(None),
+    };
+    let start = record.start().max(0) as u32;
+    let genome = fasta.fetch(var.chrom(), start as u64, record.calculate_end() as u64)?;
+    let seq = record.sequence();
+    Ok(Some(
+        record
+            .aligned_pairs()
+            .map(|(q, r)| {
+                Entry::new(
+                    q,
+                    q.map(|i| seq.at(i as usize)),
+                    r,
+                    r.and_then(|i| genome.get((i - start) as usize).copied()),
+                )
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use bam::header::Header as BamHeader;
+
+    /// Record parsed from a SAM line, on a 1000 bp contig `1`.
+    pub(crate) fn record(line: &str) -> BamRecord {
+        let mut header = BamHeader::new();
+        header.push_line("@SQ\tSN:1\tLN:1000").unwrap();
+        let mut record = BamRecord::new();
+        record.fill_from_sam(line, &header).unwrap();
+        record
+    }
+
+    #[test]
+    fn test_validate_quals() {
+        let var = Variant::try_parse("1:106C>T").unwrap();
+        let config = Config::default();
+        let read = record("r1\t0\t1\t101\t60\t10M\t*\t0\t0\tAAAAATAAAA\tIIIII#IIII\tMD:Z:5C4");
+        let support = read.validate(&var, &config).unwrap().unwrap();
+        assert_eq!(support.support, Support::Alt);
+        assert_eq!(support.quals, vec![2]);
+        assert!(support.lowbq);
+
+        // Qualities not available.
+        let read = record("r2\t0\t1\t101\t60\t10M\t*\t0\t0\tAAAAATAAAA\t*\tMD:Z:5C4");
+        let support = read.validate(&var, &config).unwrap().unwrap();
+        assert!(support.quals.is_empty());
+        assert!(!support.lowbq);
+    }
+
+    #[test]
+    fn test_validate_shifted() {
+        // Reference `GCAAAATCAC` from 101, deletion placed at the right end of `AAAA`.
+        let var = Variant::try_parse("1:102CA>C").unwrap();
+        let read = record("r1\t0\t1\t101\t60\t5M1D4M\t*\t0\t0\tGCAAATCAC\tIIII#IIII\tMD:Z:5^A4");
+        let support = read.validate(&var, &Config::default()).unwrap().unwrap();
+        assert_eq!(support.support, Support::Alt);
+        assert_eq!(support.read_pos, Some(4));
+        assert_eq!(support.quals, vec![2]);
+        assert!(support.lowbq);
+    }
+
+    #[test]
+    fn test_validate_abbr_shifted() {
+        // Reference `GCAAAATCAC` from 101, indels placed at the right end of `AAAA`.
+        let config = Config::default();
+        let var = Variant::try_parse("1:102A>-").unwrap();
+        let read = record("r1\t0\t1\t101\t60\t5M1D4M\t*\t0\t0\tGCAAATCAC\t*\tMD:Z:5^A4");
+        let support = read.validate(&var, &config).unwrap().unwrap();
+        assert_eq!(support.support, Support::Alt);
+
+        let var = Variant::try_parse("1:102->A").unwrap();
+        let read = record("r2\t0\t1\t101\t60\t2M1I8M\t*\t0\t0\tGCAAAAATCAC\t*\tMD:Z:10");
+        let support = read.validate(&var, &config).unwrap().unwrap();
+        assert_eq!(support.support, Support::Alt);
+        let read = record("r3\t0\t1\t101\t60\t6M1I4M\t*\t0\t0\tGCAAAAATCAC\t*\tMD:Z:10");
+        let support = read.validate(&var, &config).unwrap().unwrap();
+        assert_eq!(support.support, Support::Alt);
     }
 }
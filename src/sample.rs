@@ -0,0 +1,82 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::err;
+
+/// A sample and its bam file.
+#[derive(Debug, PartialEq)]
+pub struct Sample {
+    pub name: String,
+    pub bam: String,
+}
+
+impl Sample {
+    /// Sample named after bam file stem, e.g. `tumor` of `data/tumor.bam`.
+    pub fn from_bam(bam: &str) -> Self {
+        let name = Path::new(bam)
+            .file_stem()
+            .map_or_else(|| String::from(bam), |i| i.to_string_lossy().into_owned());
+        Self {
+            name,
+            bam: String::from(bam),
+        }
+    }
+}
+
+/// Read samples from a tab-separated sample sheet of sample name and bam path.
+///
+/// ## Format
+///
+/// > tumor\tdata/tumor.bam
+///
+/// ## Note
+///
+/// Empty lines and lines starting with `#` are ignored.
+pub fn from_sheet(path: &str) -> Result<Vec<Sample>, Box<dyn Error>> {
+    let mut r: Vec<Sample> = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        };
+        match line.split('\t').map(|i| i.trim()).collect::<Vec<&str>>()[..] {
+            [name, bam, ..] if !name.is_empty() && !bam.is_empty() => r.push(Sample {
+                name: String::from(name),
+                bam: String::from(bam),
+            }),
+            _ => {
+                return Err(Box::new(err(&format!(
+                "Error parsing `{}` as a sample line of `{}`, expecting sample name and bam path.",
+                line, path
+            ))))
+            }
+        }
+    }
+    Ok(r)
+}
+
+/// Check sample names unique.
+pub fn check_unique(samples: &[Sample]) -> Result<(), Box<dyn Error>> {
+    for (i, each) in samples.iter().enumerate() {
+        if samples[..i].iter().any(|j| j.name == each.name) {
+            return Err(Box::new(err(&format!(
+                "Duplicated sample name `{}`, name samples by --samples.",
+                each.name
+            ))));
+        };
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_bam() {
+        assert_eq!(Sample::from_bam("data/tumor.bam").name, "tumor");
+        assert!(check_unique(&[Sample::from_bam("a/x.bam"), Sample::from_bam("b/x.bam")]).is_err());
+    }
+}
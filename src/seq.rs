@@ -1,5 +1,9 @@
+use std::fmt;
 use std::io::Error as IOError;
 
+use bam::record::AlignmentEntry;
+use serde::Serialize;
+
 use crate::error::err;
 
 /// CIGAR Operations.
@@ -21,7 +25,7 @@ pub enum CigarOp {
 /// ## Warn
 ///
 /// N for sequencer unknown base.
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Base {
     A,
     T,
@@ -36,9 +40,10 @@ impl<'a> Base {
     /// ## Examples
     ///
     /// ```rust
-    /// use crate::seq::Base;
+    /// use vav::seq::Base;
     ///
     /// Base::try_parse("ATCC")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     /// ## Note
     ///
@@ -86,6 +91,16 @@ impl<'a> Base {
             _ => Err(err(&format!("Error parsing `{}` as valid Base", v))),
         }
     }
+
+    pub fn to_byte(&self) -> u8 {
+        match self {
+            Self::A => b'A',
+            Self::T => b'T',
+            Self::C => b'C',
+            Self::G => b'G',
+            Self::N => b'N',
+        }
+    }
 }
 
 /// Position Query Base.
@@ -102,6 +117,89 @@ pub struct QueryBase {
     querypos: u32,
 }
 
+/// Alignment entry, a position in the alignment between a read and the reference.
+///
+/// ## Note
+///
+/// Same as `bam::record::AlignmentEntry`, but constructible from reference genome
+/// for reads without MD tag.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Entry {
+    record_pos: Option<u32>,
+    record_nt: Option<u8>,
+    ref_pos: Option<u32>,
+    ref_nt: Option<u8>,
+}
+
+impl Entry {
+    pub fn new(
+        record_pos: Option<u32>,
+        record_nt: Option<u8>,
+        ref_pos: Option<u32>,
+        ref_nt: Option<u8>,
+    ) -> Self {
+        Self {
+            record_pos,
+            record_nt,
+            ref_pos,
+            ref_nt,
+        }
+    }
+
+    pub fn record_pos(&self) -> Option<u32> {
+        self.record_pos
+    }
+
+    pub fn record_nt(&self) -> Option<u8> {
+        self.record_nt
+    }
+
+    pub fn ref_pos(&self) -> Option<u32> {
+        self.ref_pos
+    }
+
+    pub fn ref_nt(&self) -> Option<u8> {
+        self.ref_nt
+    }
+
+    pub fn is_insertion(&self) -> bool {
+        self.ref_pos.is_none()
+    }
+
+    pub fn is_deletion(&self) -> bool {
+        self.record_pos.is_none()
+    }
+
+    /// Both bases present and equal, case insensitive.
+    pub fn is_seq_match(&self) -> bool {
+        match (self.record_nt, self.ref_nt) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(&b),
+            _ => false,
+        }
+    }
+}
+
+impl From<AlignmentEntry> for Entry {
+    fn from(v: AlignmentEntry) -> Self {
+        Self::new(v.record_pos(), v.record_nt(), v.ref_pos(), v.ref_nt())
+    }
+}
+
+/// Levenshtein distance between two sequences, e.g. UMIs.
+pub fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, y) in b.iter().enumerate() {
+            curr[j + 1] = (prev[j] + (x != y) as usize)
+                .min(prev[j + 1] + 1)
+                .min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
 /// Sequence support enum.
 ///
 /// ## Notes
@@ -184,7 +282,7 @@ pub struct QueryBase {
 /// ### Nul
 ///
 /// Support nothing or exceptions.
-#[derive(PartialEq)]
+#[derive(Serialize, PartialEq, Clone, Copy, Debug)]
 pub enum Support {
     /// Ref fully, ref is fully supported
     Ref,
@@ -240,6 +338,54 @@ impl Support {
     }
 }
 
+/// Reads of each `Support`, as in `Ref|Rep|Ree|Alt|Alp|Ale|Oth|Unk|Nul`.
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct Supports {
+    pub reference: u32,
+    pub partial_reference: u32,
+    pub excessive_reference: u32,
+    pub alt: u32,
+    pub partial_alt: u32,
+    pub excessive_alt: u32,
+    pub other: u32,
+    pub unknown: u32,
+    pub null: u32,
+}
+
+impl Supports {
+    pub fn add(&mut self, support: &Support) {
+        match support {
+            Support::Ref => self.reference += 1,
+            Support::Rep => self.partial_reference += 1,
+            Support::Ree => self.excessive_reference += 1,
+            Support::Alt => self.alt += 1,
+            Support::Alp => self.partial_alt += 1,
+            Support::Ale => self.excessive_alt += 1,
+            Support::Oth => self.other += 1,
+            Support::Unk => self.unknown += 1,
+            Support::Nul => self.null += 1,
+        }
+    }
+}
+
+impl fmt::Display for Supports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}",
+            self.reference,
+            self.partial_reference,
+            self.excessive_reference,
+            self.alt,
+            self.partial_alt,
+            self.excessive_alt,
+            self.other,
+            self.unknown,
+            self.null
+        )
+    }
+}
+
 /// Alignment sequence cmp.
 #[derive(PartialEq, Debug)]
 pub enum Ordering {
@@ -259,6 +405,22 @@ pub enum Ordering {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance(b"ACGT", b"ACGT"), 0);
+        assert_eq!(edit_distance(b"ACGT", b"ACTT"), 1);
+        assert_eq!(edit_distance(b"ACGT", b"CGT"), 1);
+    }
+
+    #[test]
+    fn test_supports() {
+        let mut supports = Supports::default();
+        for each in &[Support::Ref, Support::Ref, Support::Alp, Support::Nul] {
+            supports.add(each);
+        }
+        assert_eq!(supports.to_string(), "2|0|0|0|1|0|0|0|1");
+    }
+
     #[test]
     fn base_try_parse() {
         assert_eq!(
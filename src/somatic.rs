@@ -0,0 +1,164 @@
+use serde::Serialize;
+
+use crate::stats::{fisher_exact, phred, round4};
+use crate::summary::Summary;
+
+/// INFO fields of VCF annotated in tumor/normal paired mode, as (ID, Type, Description).
+///
+/// Annotated table columns are named in lowercase, e.g. `vav_somatic`.
+pub const PAIRED_INFO: [(&str, &str, &str); 2] = [
+    (
+        "VAV_SOMATIC",
+        "String",
+        "Somatic status of tumor/normal pair, one of somatic, germline, loh and artifact, by vav",
+    ),
+    (
+        "VAV_TN_FS",
+        "Float",
+        "Phred scaled Fisher's exact test p-value of tumor/normal ref/alt counts, by vav",
+    ),
+];
+
+/// Somatic status of a variant, by comparing tumor to its matched normal.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// Alt supported in tumor, absent in normal.
+    Somatic,
+    /// Alt supported in normal.
+    Germline,
+    /// Loss of heterozygosity, heterozygous in normal, shifted towards ref or alt in tumor.
+    Loh,
+    /// None above, e.g. alt barely supported in tumor, or normal not covered.
+    Artifact,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Somatic => "somatic",
+            Self::Germline => "germline",
+            Self::Loh => "loh",
+            Self::Artifact => "artifact",
+        }
+    }
+}
+
+/// Thresholds of somatic status classification.
+pub struct SomaticOptions {
+    /// Maximum normal alt fraction of somatic variants.
+    pub max_normal_af: f64,
+    /// Minimum normal alt fraction of germline variants.
+    pub min_germline_af: f64,
+    /// Minimum tumor reads properly supporting alt of somatic variants.
+    pub min_tumor_alt: u32,
+    /// Maximum tumor/normal Fisher's exact test p-value of somatic and LOH variants.
+    pub max_pvalue: f64,
+}
+
+/// Tumor/normal comparison of a variant.
+#[derive(Serialize, Debug)]
+pub struct Paired {
+    pub status: Status,
+    pub tumor_af: f64,
+    pub normal_af: f64,
+    /// Phred scaled Fisher's exact test p-value of tumor and normal ref/alt counts.
+    pub fs: f64,
+}
+
+/// Alt fraction among ref and alt supporting reads, 0 if none.
+fn alt_fraction(sum: &Summary) -> f64 {
+    match sum.ref_count() + sum.alt_count() {
+        0 => 0.0,
+        n => sum.alt_count() as f64 / n as f64,
+    }
+}
+
+impl Paired {
+    /// Classify a variant by its tumor and normal summaries.
+    ///
+    /// ## Note
+    ///
+    /// Rules in order:
+    ///
+    /// - LOH: normal alt fraction within `[min_germline_af, 1 - min_germline_af]`, tumor alt
+    ///   fraction farther from 0.5, and significantly different by Fisher's exact test.
+    /// - Germline: normal alt fraction at least `min_germline_af`.
+    /// - Somatic: tumor proper alt reads at least `min_tumor_alt`, normal alt fraction at most
+    ///   `max_normal_af`, and tumor alt fraction significantly higher by Fisher's exact test.
+    /// - Artifact: otherwise.
+    ///
+    /// ## Warn
+    ///
+    /// Variants of a normal not covered are never significant, and taken as artifacts.
+    pub fn classify(tumor: &Summary, normal: &Summary, opts: &SomaticOptions) -> Self {
+        let (taf, naf) = (alt_fraction(tumor), alt_fraction(normal));
+        let p = fisher_exact(
+            tumor.ref_count(),
+            tumor.alt_count(),
+            normal.ref_count(),
+            normal.alt_count(),
+        );
+        let significant = p <= opts.max_pvalue;
+        let status = if naf >= opts.min_germline_af {
+            if naf <= 1.0 - opts.min_germline_af
+                && significant
+                && (taf - 0.5).abs() > (naf - 0.5).abs()
+            {
+                Status::Loh
+            } else {
+                Status::Germline
+            }
+        } else if tumor.proper >= opts.min_tumor_alt
+            && naf <= opts.max_normal_af
+            && significant
+            && taf > naf
+        {
+            Status::Somatic
+        } else {
+            Status::Artifact
+        };
+        Self {
+            status,
+            tumor_af: round4(taf),
+            normal_af: round4(naf),
+            fs: round4(phred(p)),
+        }
+    }
+
+    /// Values of INFO fields, in the same order with `PAIRED_INFO`.
+    pub fn info_values(&self) -> [String; 2] {
+        [String::from(self.status.as_str()), self.fs.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn summary(reference: u32, proper: u32) -> Summary {
+        let mut sum = Summary::default();
+        sum.reference = reference;
+        sum.proper = proper;
+        sum
+    }
+
+    #[test]
+    fn test_classify() {
+        let opts = SomaticOptions {
+            max_normal_af: 0.02,
+            min_germline_af: 0.2,
+            min_tumor_alt: 3,
+            max_pvalue: 0.05,
+        };
+        let classify = |t: (u32, u32), n: (u32, u32)| {
+            Paired::classify(&summary(t.0, t.1), &summary(n.0, n.1), &opts).status
+        };
+        assert_eq!(classify((30, 10), (40, 0)), Status::Somatic);
+        assert_eq!(classify((20, 20), (21, 19)), Status::Germline);
+        assert_eq!(classify((2, 38), (20, 20)), Status::Loh);
+        assert_eq!(classify((38, 2), (40, 0)), Status::Artifact);
+        // Normal not covered.
+        assert_eq!(classify((30, 10), (0, 0)), Status::Artifact);
+    }
+}
@@ -0,0 +1,333 @@
+use serde::Serialize;
+
+/// Natural logarithm of factorials from `0!` to `n!`.
+fn ln_factorials(n: u32) -> Vec<f64> {
+    let mut r: Vec<f64> = Vec::with_capacity(n as usize + 1);
+    r.push(0.0);
+    for i in 1..=n {
+        r.push(r[i as usize - 1] + (i as f64).ln());
+    }
+    r
+}
+
+/// Two-sided Fisher's exact test p-value of a 2x2 contingency table.
+///
+/// ## Format
+///
+/// > | a | b |
+/// > | c | d |
+///
+/// ## Examples
+///
+/// ```rust
+/// # use vav::stats::fisher_exact;
+/// let p = fisher_exact(3, 1, 1, 3);
+/// assert!((p - 0.4857).abs() < 1e-4);
+/// ```
+pub fn fisher_exact(a: u32, b: u32, c: u32, d: u32) -> f64 {
+    let n = a + b + c + d;
+    if n == 0 {
+        return 1.0;
+    };
+    let lnf = ln_factorials(n);
+    let (row, col) = (a + b, a + c);
+    // Log probability of table with top left cell `x`, margins fixed.
+    let lnp = |x: u32| -> f64 {
+        lnf[row as usize] + lnf[(n - row) as usize] + lnf[col as usize] + lnf[(n - col) as usize]
+            - lnf[n as usize]
+            - lnf[x as usize]
+            - lnf[(row - x) as usize]
+            - lnf[(col - x) as usize]
+            - lnf[(n + x - row - col) as usize]
+    };
+    let observed = lnp(a);
+    let low = (row + col).saturating_sub(n);
+    let high = row.min(col);
+    let p: f64 = (low..=high)
+        .map(lnp)
+        // Relative tolerance, as in R `fisher.test`.
+        .filter(|v| *v <= observed + 1e-7)
+        .map(f64::exp)
+        .sum();
+    p.min(1.0)
+}
+
+/// Standard normal quantile of 95% two-sided confidence.
+const Z95: f64 = 1.959964;
+
+/// Significance level of detection against background error.
+const DETECTION_ALPHA: f64 = 0.05;
+
+/// Probability of detection at the limit of detection.
+const DETECTION_POWER: f64 = 0.95;
+
+/// Wilson score interval of binomial proportion `k / n`, `z` the standard normal quantile.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use vav::stats::wilson_interval;
+/// let (low, high) = wilson_interval(21, 7724, 1.959964);
+/// assert!(low < 21.0 / 7724.0 && 21.0 / 7724.0 < high);
+/// ```
+pub fn wilson_interval(k: u32, n: u32, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    };
+    let (k, n) = (k as f64, n as f64);
+    let p = k / n;
+    let z2 = z * z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+/// One-sided binomial test p-value, probability of at least `k` successes out of `n` trials
+/// of success rate `p`.
+pub fn binomial_sf(k: u32, n: u32, p: f64) -> f64 {
+    binomial_tail(&ln_factorials(n), k, n, p)
+}
+
+/// Same as `binomial_sf`, by log factorials `lnf` from `0!` to at least `n!`.
+fn binomial_tail(lnf: &[f64], k: u32, n: u32, p: f64) -> f64 {
+    if k == 0 {
+        return 1.0;
+    } else if k > n || p <= 0.0 {
+        return 0.0;
+    } else if p >= 1.0 {
+        return 1.0;
+    };
+    let (lnp, lnq) = (p.ln(), (1.0 - p).ln());
+    let v: f64 = (k..=n)
+        .map(|i| {
+            (lnf[n as usize] - lnf[i as usize] - lnf[(n - i) as usize]
+                + i as f64 * lnp
+                + (n - i) as f64 * lnq)
+                .exp()
+        })
+        .sum();
+    v.min(1.0)
+}
+
+/// Limit of detection at depth `n`, the lowest alt fraction detected with 95% probability,
+/// by at least as many alt reads as significant against background `error` rate.
+///
+/// `None` if even all `n` reads supporting alt not significant, e.g. `n` is 0.
+pub fn limit_of_detection(n: u32, error: f64) -> Option<f64> {
+    let lnf = ln_factorials(n);
+    let sf = |k: u32, p: f64| binomial_tail(&lnf, k, n, p);
+    if n == 0 || sf(n, error) > DETECTION_ALPHA {
+        return None;
+    };
+    // Least alt reads significant, tail probability decreasing as `k` grows.
+    let (mut low, mut high) = (1, n);
+    while low < high {
+        let mid = (low + high) / 2;
+        if sf(mid, error) <= DETECTION_ALPHA {
+            high = mid;
+        } else {
+            low = mid + 1;
+        };
+    }
+    let k = high;
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..50 {
+        let mid = (low + high) / 2.0;
+        if sf(k, mid) >= DETECTION_POWER {
+            high = mid;
+        } else {
+            low = mid;
+        };
+    }
+    Some(high)
+}
+
+/// Alt fraction confidence interval and detection against background error.
+#[derive(Serialize, Default)]
+pub struct Detection {
+    /// Lower bound of 95% Wilson score interval of alt fraction.
+    pub af_low: f64,
+    /// Upper bound of 95% Wilson score interval of alt fraction.
+    pub af_high: f64,
+    /// Binomial test p-value of alt reads against background error rate.
+    pub pvalue: f64,
+    /// Alt significant against background error, at 0.05.
+    pub detected: bool,
+    /// Limit of detection at the depth, see `limit_of_detection`.
+    pub lod: Option<f64>,
+}
+
+impl Detection {
+    pub fn new(alt: u32, total: u32, error: f64) -> Self {
+        let (low, high) = wilson_interval(alt, total, Z95);
+        let pvalue = binomial_sf(alt, total, error);
+        Self {
+            af_low: round4(low),
+            af_high: round4(high),
+            pvalue,
+            detected: alt > 0 && pvalue <= DETECTION_ALPHA,
+            lod: limit_of_detection(total, error).map(round4),
+        }
+    }
+}
+
+/// Phred scaled p-value, `-10 * log10(p)`.
+pub fn phred(p: f64) -> f64 {
+    let v = -10.0 * p.max(f64::MIN_POSITIVE).log10();
+    // Avoid `-0`, `f64::max` may keep it.
+    if v > 0.0 {
+        v
+    } else {
+        0.0
+    }
+}
+
+/// GATK style symmetric odds ratio of a 2x2 contingency table, pseudocount 1 added to each cell.
+///
+/// ## Format
+///
+/// > | ref forward | ref reverse |
+/// > | alt forward | alt reverse |
+pub fn strand_odds_ratio(rf: u32, rr: u32, af: u32, ar: u32) -> f64 {
+    let (rf, rr, af, ar) = (
+        rf as f64 + 1.0,
+        rr as f64 + 1.0,
+        af as f64 + 1.0,
+        ar as f64 + 1.0,
+    );
+    let ratio = (rf * ar) / (rr * af);
+    let symmetrical = ratio + 1.0 / ratio;
+    let ref_ratio = rf.min(rr) / rf.max(rr);
+    let alt_ratio = af.min(ar) / af.max(ar);
+    symmetrical.ln() + ref_ratio.ln() - alt_ratio.ln()
+}
+
+/// Round to 4 decimal places.
+pub fn round4(v: f64) -> f64 {
+    (v * 10000.0).round() / 10000.0
+}
+
+/// Histogram of phred scaled qualities.
+#[derive(Default)]
+pub struct QualityHistogram {
+    counts: Vec<u64>,
+}
+
+impl QualityHistogram {
+    pub fn add(&mut self, q: u8) {
+        if self.counts.len() <= q as usize {
+            self.counts.resize(q as usize + 1, 0);
+        };
+        self.counts[q as usize] += 1;
+    }
+
+    pub fn extend(&mut self, qs: &[u8]) {
+        qs.iter().for_each(|q| self.add(*q));
+    }
+
+    pub fn len(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        let n = self.len();
+        if n == 0 {
+            return None;
+        };
+        let sum: u64 = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(q, c)| q as u64 * c)
+            .sum();
+        Some(round4(sum as f64 / n as f64))
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        let n = self.len();
+        if n == 0 {
+            return None;
+        };
+        Some((self.nth((n - 1) / 2) + self.nth(n / 2)) as f64 / 2.0)
+    }
+
+    /// The `i`th (0-based) smallest quality.
+    fn nth(&self, i: u64) -> usize {
+        let mut acc = 0;
+        for (q, c) in self.counts.iter().enumerate() {
+            acc += c;
+            if acc > i {
+                return q;
+            };
+        }
+        self.counts.len() - 1
+    }
+}
+
+/// Mean and median of base qualities.
+#[derive(Serialize, Default)]
+pub struct QualityStats {
+    pub mean: Option<f64>,
+    pub median: Option<f64>,
+}
+
+impl From<&QualityHistogram> for QualityStats {
+    fn from(v: &QualityHistogram) -> Self {
+        Self {
+            mean: v.mean(),
+            median: v.median(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fisher_exact() {
+        // R: fisher.test(matrix(c(3, 1, 1, 3), nrow = 2))$p.value
+        assert!((fisher_exact(3, 1, 1, 3) - 0.4857143).abs() < 1e-6);
+        // R: fisher.test(matrix(c(10, 0, 2, 12), nrow = 2))$p.value
+        assert!((fisher_exact(10, 2, 0, 12) - 6.730381e-05).abs() < 1e-10);
+        assert_eq!(fisher_exact(0, 0, 0, 0), 1.0);
+    }
+
+    #[test]
+    fn test_phred() {
+        assert_eq!(phred(1.0), 0.0);
+        // `-0` equals `0`, but is printed as `-0` in FS.
+        assert!(phred(1.0).is_sign_positive());
+        assert_eq!(phred(1.0).to_string(), "0");
+        assert_eq!(phred(0.01), 20.0);
+    }
+
+    #[test]
+    fn test_detection() {
+        // R: binom.test(3, 10, 0.1, alternative = "greater")$p.value
+        assert!((binomial_sf(3, 10, 0.1) - 0.07019083).abs() < 1e-6);
+        // R: prop.test(21, 7724, correct = FALSE)$conf.int
+        let (low, high) = wilson_interval(21, 7724, Z95);
+        assert!((low - 0.001779).abs() < 1e-5 && (high - 0.004153).abs() < 1e-5);
+        assert_eq!(limit_of_detection(0, 0.001), None);
+        // 2 of 100 reads significant against 0.1% error, detected at 4.7% with 95% probability.
+        let lod = limit_of_detection(100, 0.001).unwrap();
+        assert!((lod - 0.0466).abs() < 1e-3);
+        assert_eq!(limit_of_detection(1, 0.5), None);
+        // Deep and noisy, k near 5.3% of reads.
+        let lod = limit_of_detection(100000, 0.05).unwrap();
+        assert!(0.05 < lod && lod < 0.06);
+    }
+
+    #[test]
+    fn test_quality_histogram() {
+        let mut hist = QualityHistogram::default();
+        hist.extend(&[30, 10, 20, 37]);
+        assert_eq!(hist.mean(), Some(24.25));
+        assert_eq!(hist.median(), Some(25.0));
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+use bam::record::Flag;
+use serde::Serialize;
+
+use crate::stats::{fisher_exact, phred, round4, strand_odds_ratio};
+use crate::summary::Category;
+
+/// Support count splitted by strand and read of pair, as in `F1:F2:R1:R2`.
+///
+/// ## Warn
+///
+/// Unpaired reads are counted as read1.
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct Strands {
+    /// Forward strand read1.
+    pub f1: u32,
+    /// Forward strand read2.
+    pub f2: u32,
+    /// Reverse strand read1.
+    pub r1: u32,
+    /// Reverse strand read2.
+    pub r2: u32,
+}
+
+impl Strands {
+    /// Count a read by its flag.
+    pub fn add(&mut self, flag: &Flag) {
+        match (flag.is_reverse_strand(), flag.last_in_pair()) {
+            (false, false) => self.f1 += 1,
+            (false, true) => self.f2 += 1,
+            (true, false) => self.r1 += 1,
+            (true, true) => self.r2 += 1,
+        }
+    }
+
+    pub fn forward(&self) -> u32 {
+        self.f1 + self.f2
+    }
+
+    pub fn reverse(&self) -> u32 {
+        self.r1 + self.r2
+    }
+
+    pub fn total(&self) -> u32 {
+        self.forward() + self.reverse()
+    }
+}
+
+impl fmt::Display for Strands {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}:{}", self.f1, self.f2, self.r1, self.r2)
+    }
+}
+
+/// Strand breakdown of each `Summary` category.
+#[derive(Serialize, Default)]
+pub struct Breakdown {
+    pub reference: Strands,
+    pub proper: Strands,
+    pub margin: Strands,
+    pub lowq: Strands,
+    pub lowbq: Strands,
+    pub excessive: Strands,
+    pub alleles: Strands,
+    pub unknown: Strands,
+}
+
+impl Breakdown {
+    /// Strands of a category.
+    pub fn get_mut(&mut self, category: Category) -> &mut Strands {
+        match category {
+            Category::Reference => &mut self.reference,
+            Category::Proper => &mut self.proper,
+            Category::Margin => &mut self.margin,
+            Category::Lowq => &mut self.lowq,
+            Category::Lowbq => &mut self.lowbq,
+            Category::Excessive => &mut self.excessive,
+            Category::Alleles => &mut self.alleles,
+            Category::Unknown => &mut self.unknown,
+        }
+    }
+
+    /// Strands of alt support, sum of proper, margin, lowq, lowbq and excessive.
+    pub fn alt(&self) -> Strands {
+        let cats = [
+            &self.proper,
+            &self.margin,
+            &self.lowq,
+            &self.lowbq,
+            &self.excessive,
+        ];
+        Strands {
+            f1: cats.iter().map(|i| i.f1).sum(),
+            f2: cats.iter().map(|i| i.f2).sum(),
+            r1: cats.iter().map(|i| i.r1).sum(),
+            r2: cats.iter().map(|i| i.r2).sum(),
+        }
+    }
+}
+
+/// Strand bias of ref and alt supports.
+#[derive(Serialize, Default)]
+pub struct StrandBias {
+    /// Fisher's exact test p-value.
+    pub fisher: f64,
+    /// Phred scaled Fisher's exact test p-value, GATK FS.
+    pub fs: f64,
+    /// GATK strand odds ratio, SOR.
+    pub sor: f64,
+}
+
+impl StrandBias {
+    pub fn new(reference: &Strands, alt: &Strands) -> Self {
+        let (rf, rr, af, ar) = (
+            reference.forward(),
+            reference.reverse(),
+            alt.forward(),
+            alt.reverse(),
+        );
+        let fisher = fisher_exact(rf, rr, af, ar);
+        Self {
+            fisher,
+            fs: round4(phred(fisher)),
+            sor: round4(strand_odds_ratio(rf, rr, af, ar)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_strands_add() {
+        let mut strands = Strands::default();
+        // Reverse strand read2, forward strand read1, unpaired reverse strand read.
+        strands.add(&Flag(147));
+        strands.add(&Flag(99));
+        strands.add(&Flag(16));
+        assert_eq!(strands.to_string(), "1:0:1:1")
+    }
+}
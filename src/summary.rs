@@ -0,0 +1,749 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use bam::record::Record as BamRecord;
+use serde::Serialize;
+
+use crate::evidence::Evidence;
+use crate::filter::{Filtered, Reason};
+use crate::genotype::{Genotype, Likelihoods};
+use crate::record::{ReadSupport, VariantValidate};
+use crate::seq::{Support, Supports};
+use crate::stats::{Detection, QualityHistogram, QualityStats};
+use crate::strand::{Breakdown, StrandBias};
+use crate::umi::{Families, UmiReads};
+use crate::validator::Config;
+use crate::variant::Variant;
+
+/// INFO fields of annotated VCF, as (ID, Type, Description), one value for each ALT.
+///
+/// Annotated table columns are named in lowercase, e.g. `vav_ref`, followed by `STRAND_COLUMNS`.
+pub const VCF_INFO: [(&str, &str, &str); 18] = [
+    (
+        "VAV_REF",
+        "Integer",
+        "Number of reads supporting reference, by vav",
+    ),
+    (
+        "VAV_PROPER",
+        "Integer",
+        "Number of reads properly supporting alt, by vav",
+    ),
+    (
+        "VAV_MARGIN",
+        "Integer",
+        "Number of reads supporting alt within read margin, by vav",
+    ),
+    (
+        "VAV_LOWQ",
+        "Integer",
+        "Number of reads of low mapping quality supporting alt, by vav",
+    ),
+    (
+        "VAV_LOWBQ",
+        "Integer",
+        "Number of reads of low base quality supporting alt, by vav",
+    ),
+    (
+        "VAV_EXCESSIVE",
+        "Integer",
+        "Number of reads excessively supporting alt, by vav",
+    ),
+    (
+        "VAV_ALLELES",
+        "Integer",
+        "Number of reads supporting other alleles, by vav",
+    ),
+    (
+        "VAV_UNKNOWN",
+        "Integer",
+        "Number of reads of unknown support, e.g. MD tag missing, by vav",
+    ),
+    (
+        "VAV_FILTERED",
+        "Integer",
+        "Number of reads filtered out by flag or not spanning the variant, by vav",
+    ),
+    (
+        "VAV_AF",
+        "Float",
+        "Alt allele frequency of all alt supporting reads, by vav",
+    ),
+    (
+        "VAV_AF_LOW",
+        "Float",
+        "Lower bound of 95% Wilson score interval of alt allele frequency, by vav",
+    ),
+    (
+        "VAV_AF_HIGH",
+        "Float",
+        "Upper bound of 95% Wilson score interval of alt allele frequency, by vav",
+    ),
+    (
+        "VAV_DETECTION_P",
+        "Float",
+        "Binomial test p-value of alt supporting reads against background error rate, by vav",
+    ),
+    (
+        "VAV_LOD",
+        "Float",
+        "Limit of detection, alt allele frequency detected with 95% probability at the depth, by vav",
+    ),
+    (
+        "VAV_FS",
+        "Float",
+        "Phred scaled Fisher's exact test p-value of strand bias, by vav",
+    ),
+    (
+        "VAV_SOR",
+        "Float",
+        "Symmetric odds ratio of strand bias, by vav",
+    ),
+    (
+        "VAV_NORM",
+        "String",
+        "Normalized variant validated against reads, by vav",
+    ),
+    (
+        "VAV_SUPPORTS",
+        "String",
+        "Number of reads of each support, as Ref|Rep|Ree|Alt|Alp|Ale|Oth|Unk|Nul, by vav",
+    ),
+];
+
+/// FORMAT fields of genotypes called from reads, as (ID, Number, Type, Description).
+pub const GENOTYPE_FORMAT: [(&str, &str, &str, &str); 4] = [
+    (
+        "VAV_GT",
+        "A",
+        "String",
+        "Genotype of each ALT called from reads, one of 0/0, 0/1 and 1/1, by vav",
+    ),
+    (
+        "VAV_GQ",
+        "A",
+        "Integer",
+        "Genotype quality of each ALT, by vav",
+    ),
+    (
+        "VAV_PL",
+        ".",
+        "Integer",
+        "Phred scaled likelihoods of 0/0, 0/1 and 1/1 of each ALT in turn, by vav",
+    ),
+    (
+        "VAV_GT_DISCORDANT",
+        "A",
+        "Integer",
+        "Whether GT disagrees with genotype called from reads of each ALT, 1 if so, by vav",
+    ),
+];
+
+/// Extra columns of annotated table, supports splitted as `F1:F2:R1:R2`.
+pub const STRAND_COLUMNS: [&str; 9] = [
+    "vav_ref_strands",
+    "vav_proper_strands",
+    "vav_margin_strands",
+    "vav_lowq_strands",
+    "vav_lowbq_strands",
+    "vav_excessive_strands",
+    "vav_alleles_strands",
+    "vav_unknown_strands",
+    "vav_alt_strands",
+];
+
+/// Summary category a read is counted in.
+///
+/// ## Warn
+///
+/// Declaration order matters, the former the better when reconciling mates of a fragment.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Category {
+    Reference,
+    Proper,
+    Margin,
+    Lowq,
+    Lowbq,
+    Excessive,
+    Alleles,
+    Unknown,
+}
+
+/// Allele supported by a read, reads supporting the same allele agree with each other.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Allele {
+    Reference,
+    Alt,
+    Other,
+    Unknown,
+}
+
+impl From<&ReadSupport> for Category {
+    /// Category of a read by its support.
+    ///
+    /// ## Note
+    ///
+    /// - Fully, partially and excessively supported ref are all counted as reference.
+    /// - Fully supported alt is counted as lowq, lowbq, margin or proper in order.
+    /// - Partially supported alt is counted as other alleles.
+    fn from(v: &ReadSupport) -> Self {
+        match v.support {
+            Support::Ref | Support::Rep | Support::Ree => Self::Reference,
+            Support::Alt if v.lowq => Self::Lowq,
+            Support::Alt if v.lowbq => Self::Lowbq,
+            Support::Alt if v.margin => Self::Margin,
+            Support::Alt => Self::Proper,
+            Support::Ale => Self::Excessive,
+            Support::Alp | Support::Oth | Support::Nul => Self::Alleles,
+            Support::Unk => Self::Unknown,
+        }
+    }
+}
+
+impl Category {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reference => "reference",
+            Self::Proper => "proper",
+            Self::Margin => "margin",
+            Self::Lowq => "lowq",
+            Self::Lowbq => "lowbq",
+            Self::Excessive => "excessive",
+            Self::Alleles => "alleles",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    pub fn allele(&self) -> Allele {
+        match self {
+            Self::Reference => Allele::Reference,
+            Self::Proper | Self::Margin | Self::Lowq | Self::Lowbq | Self::Excessive => Allele::Alt,
+            Self::Alleles => Allele::Other,
+            Self::Unknown => Allele::Unknown,
+        }
+    }
+
+    /// Reconcile categories of reads from the same fragment, `None` if reads disagree.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use vav::summary::Category;
+    /// assert_eq!(
+    ///     Category::reconcile(&[Category::Margin, Category::Proper]),
+    ///     Some(Category::Proper)
+    /// );
+    /// assert_eq!(
+    ///     Category::reconcile(&[Category::Reference, Category::Proper]),
+    ///     None
+    /// );
+    /// ```
+    ///
+    /// ## Note
+    ///
+    /// Unknown support is taken as by `Category::informative`.
+    pub fn reconcile(cats: &[Category]) -> Option<Category> {
+        let informative = Self::informative(cats);
+        let allele = informative[0].allele();
+        if informative.iter().all(|i| i.allele() == allele) {
+            informative.into_iter().min()
+        } else {
+            None
+        }
+    }
+
+    /// Categories of informative reads, never empty.
+    ///
+    /// ## Note
+    ///
+    /// Unknown support is uninformative, and ignored unless all reads are unknown.
+    pub fn informative(cats: &[Category]) -> Vec<Category> {
+        let r: Vec<Category> = cats
+            .iter()
+            .copied()
+            .filter(|i| *i != Self::Unknown)
+            .collect();
+        if r.is_empty() {
+            vec![Self::Unknown]
+        } else {
+            r
+        }
+    }
+}
+
+/// Fragment level supports, mates of a read pair counted once.
+#[derive(Serialize, Default)]
+pub struct Fragments {
+    pub reference: u32,
+    pub proper: u32,
+    pub margin: u32,
+    pub lowq: u32,
+    pub lowbq: u32,
+    pub excessive: u32,
+    pub alleles: u32,
+    pub unknown: u32,
+    /// Mates disagree with each other, e.g. one supporting ref while the other alt.
+    pub discordant: u32,
+}
+
+impl Fragments {
+    fn add(&mut self, category: Option<Category>) {
+        match category {
+            Some(Category::Reference) => self.reference += 1,
+            Some(Category::Proper) => self.proper += 1,
+            Some(Category::Margin) => self.margin += 1,
+            Some(Category::Lowq) => self.lowq += 1,
+            Some(Category::Lowbq) => self.lowbq += 1,
+            Some(Category::Excessive) => self.excessive += 1,
+            Some(Category::Alleles) => self.alleles += 1,
+            Some(Category::Unknown) => self.unknown += 1,
+            None => self.discordant += 1,
+        }
+    }
+}
+
+#[derive(Serialize, Default)]
+pub struct Summary {
+    /// Input variant, set if normalized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    /// Normalized variant, validated instead of input, set if reference genome given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalized: Option<String>,
+    /// Ref support, counted from `strands` by `conclude`, as are the categories below.
+    pub reference: u32,
+    /// Alt proper support.
+    pub proper: u32,
+    /// Alt support in margin.
+    pub margin: u32,
+    /// Alt support of low mapq.
+    pub lowq: u32,
+    /// Alt support of low base quality.
+    pub lowbq: u32,
+    /// Alt support of excessive support.
+    /// Example, expecting chr1:12345A>C, got chr1:12345AT>CG, chr1:12345AT>C, etc.
+    pub excessive: u32,
+    /// Other alleles support.
+    pub alleles: u32,
+    /// Unknown support or exception.
+    pub unknown: u32,
+    /// Reads of each `Support`, before merged into categories above.
+    pub supports: Supports,
+    /// Reads filtered out by flag or as partial, not counted in any support.
+    pub filtered: Filtered,
+    /// Supports splitted by strand and read of pair.
+    pub strands: Breakdown,
+    /// Strand bias of ref and alt supports, set by `conclude`.
+    pub strand_bias: StrandBias,
+    /// Alt fraction confidence interval and limit of detection, set by `conclude`.
+    pub detection: Detection,
+    /// Base qualities of ref and alt supporting bases, set by `conclude`.
+    pub baseq: BaseQuality,
+    /// Fragment level supports, set by `conclude` in fragment mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fragments: Option<Fragments>,
+    /// UMI family level supports, set by `conclude` in UMI mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub families: Option<Families>,
+    /// Genotype called from ref and alt supports, set by `conclude` if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genotype: Option<Genotype>,
+    #[serde(skip)]
+    likelihoods: Likelihoods,
+    /// Evidence of reads overlapping the variant, if `Config::evidence`.
+    #[serde(skip)]
+    pub reads: Vec<Evidence>,
+    /// Counted reads and their categories, if `Config::records`.
+    #[serde(skip)]
+    pub records: Vec<(Category, BamRecord)>,
+    #[serde(skip)]
+    ref_quals: QualityHistogram,
+    #[serde(skip)]
+    alt_quals: QualityHistogram,
+    /// Categories of reads, by read name, in fragment mode.
+    #[serde(skip)]
+    mates: HashMap<Vec<u8>, Vec<Category>>,
+    /// Categories of reads, by UMI, in UMI mode.
+    #[serde(skip)]
+    umis: UmiReads,
+}
+
+/// Base qualities of ref and alt supporting bases.
+#[derive(Serialize, Default)]
+pub struct BaseQuality {
+    pub reference: QualityStats,
+    pub alt: QualityStats,
+}
+
+impl Summary {
+    pub fn total_count(&self) -> u32 {
+        self.reference
+            + self.proper
+            + self.margin
+            + self.lowq
+            + self.lowbq
+            + self.excessive
+            + self.alleles
+            + self.unknown
+    }
+
+    pub fn alt_count(&self) -> u32 {
+        self.proper + self.margin + self.lowq + self.lowbq + self.excessive
+    }
+
+    pub fn alt_freq(&self) -> f32 {
+        let v = self.alt_count() as f32 / self.total_count() as f32;
+        (v * 10000.0).round() / 10000.0
+    }
+
+    pub fn proper_freq(&self) -> f32 {
+        let v = self.proper as f32 / self.total_count() as f32;
+        (v * 10000.0).round() / 10000.0
+    }
+
+    pub fn margin_freq(&self) -> f32 {
+        let v = self.margin as f32 / self.total_count() as f32;
+        (v * 10000.0).round() / 10000.0
+    }
+
+    pub fn lowq_freq(&self) -> f32 {
+        let v = self.lowq as f32 / self.total_count() as f32;
+        (v * 10000.0).round() / 10000.0
+    }
+
+    pub fn lowbq_freq(&self) -> f32 {
+        let v = self.lowbq as f32 / self.total_count() as f32;
+        (v * 10000.0).round() / 10000.0
+    }
+
+    pub fn ref_count(&self) -> u32 {
+        self.reference
+    }
+
+    pub fn ref_freq(&self) -> f32 {
+        let v = self.ref_count() as f32 / self.total_count() as f32;
+        (v * 10000.0).round() / 10000.0
+    }
+
+    /// Values of INFO fields, in the same order with `VCF_INFO`.
+    pub fn info_values(&self) -> [String; 18] {
+        [
+            self.reference.to_string(),
+            self.proper.to_string(),
+            self.margin.to_string(),
+            self.lowq.to_string(),
+            self.lowbq.to_string(),
+            self.excessive.to_string(),
+            self.alleles.to_string(),
+            self.unknown.to_string(),
+            self.filtered.total().to_string(),
+            if self.total_count() > 0 {
+                self.alt_freq().to_string()
+            } else {
+                String::from(".")
+            },
+            if self.total_count() > 0 {
+                self.detection.af_low.to_string()
+            } else {
+                String::from(".")
+            },
+            if self.total_count() > 0 {
+                self.detection.af_high.to_string()
+            } else {
+                String::from(".")
+            },
+            format!("{:.3e}", self.detection.pvalue),
+            self.detection
+                .lod
+                .map_or_else(|| String::from("."), |i| i.to_string()),
+            self.strand_bias.fs.to_string(),
+            self.strand_bias.sor.to_string(),
+            self.normalized.clone().unwrap_or_else(|| String::from(".")),
+            self.supports.to_string(),
+        ]
+    }
+
+    /// Values of FORMAT fields, in the same order with `GENOTYPE_FORMAT`.
+    pub fn genotype_values(&self) -> [String; 4] {
+        match self.genotype {
+            Some(ref v) => [
+                v.gt.clone(),
+                v.gq.to_string(),
+                v.pl.iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+                match v.concordant {
+                    Some(true) => String::from("0"),
+                    Some(false) => String::from("1"),
+                    None => String::from("."),
+                },
+            ],
+            None => [
+                String::from("."),
+                String::from("."),
+                String::from("."),
+                String::from("."),
+            ],
+        }
+    }
+
+    /// Category counts from the strand breakdown, the only tally of reads while validating.
+    fn count(&mut self) {
+        let strands = &self.strands;
+        self.reference = strands.reference.total();
+        self.proper = strands.proper.total();
+        self.margin = strands.margin.total();
+        self.lowq = strands.lowq.total();
+        self.lowbq = strands.lowbq.total();
+        self.excessive = strands.excessive.total();
+        self.alleles = strands.alleles.total();
+        self.unknown = strands.unknown.total();
+    }
+
+    /// Compute statistics after all records validated.
+    pub fn conclude(&mut self, config: &Config) {
+        self.count();
+        self.strand_bias = StrandBias::new(&self.strands.reference, &self.strands.alt());
+        self.baseq = BaseQuality {
+            reference: QualityStats::from(&self.ref_quals),
+            alt: QualityStats::from(&self.alt_quals),
+        };
+        self.genotype = self.likelihoods.call();
+        self.detection = Detection::new(self.alt_count(), self.total_count(), config.error_rate);
+        if config.fragments {
+            let mut fragments = Fragments::default();
+            for cats in self.mates.values() {
+                fragments.add(Category::reconcile(cats));
+            }
+            self.fragments = Some(fragments);
+        };
+        if let Some(ref opts) = config.umi {
+            self.families = Some(self.umis.families(opts));
+        };
+    }
+
+    /// Values of `STRAND_COLUMNS`.
+    pub fn strand_values(&self) -> [String; 9] {
+        [
+            self.strands.reference.to_string(),
+            self.strands.proper.to_string(),
+            self.strands.margin.to_string(),
+            self.strands.lowq.to_string(),
+            self.strands.lowbq.to_string(),
+            self.strands.excessive.to_string(),
+            self.strands.alleles.to_string(),
+            self.strands.unknown.to_string(),
+            self.strands.alt().to_string(),
+        ]
+    }
+
+    /// Validate record supportion for variant.
+    ///
+    /// Records overlapping but not spanning the whole variant are filtered out as partial.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use bam::record::Record as BamRecord;
+    ///
+    /// use vav::{Config, Summary, Variant};
+    ///
+    /// let var = Variant::try_parse("chr1:123456AT>-")?;
+    /// let record = BamRecord::new();
+    /// let mut sum = Summary::default();
+    /// sum.validate(&record, &var, &Config::default())?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// ## Warn
+    ///
+    /// Crate `bam` bam reader reading alignemnt with 0-based position, while variant is 1-based.
+    /// So alignment `+1` or variant `-1` is necessary in some places.
+    ///
+    pub fn validate(
+        &mut self,
+        record: &BamRecord,
+        var: &Variant,
+        config: &Config,
+    ) -> Result<(), Box<dyn Error>> {
+        // Out of variant region
+        if (record.start() + 1) as u32 > var.end() || (record.calculate_end() as u32) < var.pos() {
+            return Ok(());
+        }
+        let spanning = (record.start() + 1) as u32 <= var.pos()
+            && (record.calculate_end() as u32) >= var.end();
+        let reason = match config.filter.reason(&record.flag()) {
+            None if !spanning => Some(Reason::Partial),
+            v => v,
+        };
+        let read = match reason {
+            Some(ref v) => {
+                log::debug!(
+                    "Filtered record `{}` as {:?}",
+                    String::from_utf8_lossy(record.name()),
+                    v
+                );
+                self.filtered.add(v);
+                None
+            }
+            None => record.validate(var, config)?,
+        };
+        let category = read.as_ref().map(Category::from);
+        if let (Some(v), Some(category)) = (&read, category) {
+            self.supports.add(&v.support);
+            self.add(category, record, &v.quals, config);
+            if config.records {
+                self.records.push((category, record.clone()));
+            };
+        };
+        if config.evidence {
+            self.reads
+                .push(Evidence::new(record, read.as_ref(), category, reason));
+        };
+        Ok(())
+    }
+
+    /// Count a read in `category`, with base qualities of its variant bases.
+    fn add(&mut self, category: Category, record: &BamRecord, quals: &[u8], config: &Config) {
+        let flag = record.flag();
+        // Genotype evidence, ref and alt reads passing mapq and base quality thresholds, margin
+        // not applied to alt reads as it is not to ref reads.
+        let passing = record.mapq() >= config.mapq && quals.iter().all(|q| *q >= config.min_baseq);
+        let counted = matches!(
+            category,
+            Category::Reference | Category::Proper | Category::Margin
+        );
+        if passing && counted {
+            self.likelihoods
+                .add(record.name(), category.allele(), quals);
+        };
+        self.strands.get_mut(category).add(&flag);
+        match category.allele() {
+            Allele::Reference => self.ref_quals.extend(quals),
+            Allele::Alt => self.alt_quals.extend(quals),
+            _ => {}
+        };
+        if config.fragments {
+            self.mates
+                .entry(record.name().to_vec())
+                .or_default()
+                .push(category);
+        };
+        if let Some(ref opts) = config.umi {
+            self.umis.add(record, category, opts);
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::record::test::record;
+
+    #[test]
+    fn test_reconcile() {
+        assert_eq!(
+            Category::reconcile(&[Category::Margin, Category::Proper]),
+            Some(Category::Proper)
+        );
+        assert_eq!(
+            Category::reconcile(&[Category::Unknown, Category::Reference]),
+            Some(Category::Reference)
+        );
+        assert_eq!(
+            Category::reconcile(&[Category::Reference, Category::Lowq]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_informative() {
+        assert_eq!(
+            Category::informative(&[Category::Unknown, Category::Margin]),
+            vec![Category::Margin]
+        );
+        assert_eq!(
+            Category::informative(&[Category::Unknown, Category::Unknown]),
+            vec![Category::Unknown]
+        );
+        assert_eq!(Category::informative(&[]), vec![Category::Unknown]);
+    }
+
+    #[test]
+    fn test_category_from() {
+        let read = |support: Support, lowq: bool, margin: bool| ReadSupport {
+            support,
+            lowq,
+            lowbq: false,
+            margin,
+            read_pos: None,
+            distances: (0, 0),
+            observed_ref: Vec::new(),
+            observed_alt: Vec::new(),
+            quals: Vec::new(),
+        };
+        let cat = |v: ReadSupport| Category::from(&v);
+        assert_eq!(cat(read(Support::Ree, true, true)), Category::Reference);
+        assert_eq!(cat(read(Support::Alt, true, true)), Category::Lowq);
+        assert_eq!(cat(read(Support::Alt, false, true)), Category::Margin);
+        assert_eq!(cat(read(Support::Alp, false, false)), Category::Alleles);
+    }
+
+    #[test]
+    fn test_validate_partial() {
+        let config = Config {
+            evidence: true,
+            ..Config::default()
+        };
+        let var = Variant::try_parse("1:105AT>A").unwrap();
+        let mut sum = Summary::default();
+        let read = record("r1\t0\t1\t101\t60\t5M\t*\t0\t0\tAAAAA\t*\tMD:Z:5");
+        sum.validate(&read, &var, &config).unwrap();
+        assert_eq!(sum.filtered.partial, 1);
+        assert_eq!(sum.reads.len(), 1);
+        assert_eq!(sum.reads[0].filter, Some(Reason::Partial));
+    }
+
+    #[test]
+    fn test_count() {
+        let config = Config::default();
+        let mut sum = Summary::default();
+        let mut record = BamRecord::new();
+        record.set_flag(16);
+        sum.add(Category::Proper, &record, &[30], &config);
+        sum.add(Category::Reference, &record, &[30], &config);
+        sum.add(Category::Reference, &record, &[30], &config);
+        sum.conclude(&config);
+        assert_eq!((sum.reference, sum.proper, sum.margin), (2, 1, 0));
+        assert_eq!(sum.strands.proper.to_string(), "0:0:1:0");
+        assert_eq!(sum.total_count(), 3);
+    }
+
+    #[test]
+    fn test_genotype_margin() {
+        let config = Config::default();
+        let mut sum = Summary::default();
+        for (i, category) in [Category::Margin, Category::Margin, Category::Reference]
+            .iter()
+            .enumerate()
+        {
+            let mut record = BamRecord::new();
+            record.set_name(format!("r{}", i).into_bytes());
+            record.set_mapq(60);
+            sum.add(*category, &record, &[30], &config);
+        }
+        sum.conclude(&config);
+        // Alt reads near read ends as genotype evidence, same as ref reads.
+        assert_eq!(sum.genotype.unwrap().gt, "0/1");
+    }
+
+    #[test]
+    fn test_info_values() {
+        let sum = Summary {
+            detection: Detection::new(100, 1000, 0.001),
+            ..Default::default()
+        };
+        assert_eq!(sum.info_values()[12], "2.618e-161");
+    }
+}
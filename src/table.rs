@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+use crate::error::err;
+use crate::seq::Base;
+use crate::variant::Variant;
+
+/// Tab-separated variant table, e.g. `tests/variants_table.xls`.
+///
+/// ## Format
+///
+/// First line is the header, columns of chromosome, start position, ref and alt are
+/// located by their names. Ref and alt are taken as in `--var`, `-` stands for null.
+#[derive(Debug)]
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    /// Column index of chromosome, start position, ref and alt.
+    columns: [usize; 4],
+}
+
+impl Table {
+    /// Read table from path.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use vav::table::Table;
+    /// let table = Table::from_path(
+    ///     "tests/variants_table.xls",
+    ///     &["Chromosome", "Start_position", "Ref", "Alt"],
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_path(path: &str, names: &[&str]) -> Result<Self, Box<dyn Error>> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        let header: Vec<String> = match lines.next() {
+            Some(v) => v?.split('\t').map(String::from).collect(),
+            None => return Err(Box::new(err(&format!("Empty table `{}`.", path)))),
+        };
+        if names.len() != 4 {
+            return Err(Box::new(err(&format!(
+                "Expecting 4 column names of chromosome, start position, ref and alt, got {:?}.",
+                names
+            ))));
+        };
+        let mut columns = [0usize; 4];
+        for (i, name) in names.iter().enumerate() {
+            columns[i] = header
+                .iter()
+                .position(|v| v == name)
+                .ok_or_else(|| err(&format!("Column `{}` not found in table `{}`.", name, path)))?;
+        }
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            };
+            let row: Vec<String> = line.split('\t').map(String::from).collect();
+            if row.len() < header.len() {
+                return Err(Box::new(err(&format!(
+                    "Expecting {} columns, got {} in table line `{}`.",
+                    header.len(),
+                    row.len(),
+                    line
+                ))));
+            };
+            rows.push(row);
+        }
+        Ok(Self {
+            header,
+            rows,
+            columns,
+        })
+    }
+
+    /// Variants, one for each row.
+    pub fn variants(&self) -> Result<Vec<Variant>, Box<dyn Error>> {
+        let [chrom, pos, refs, alts] = self.columns;
+        self.rows
+            .iter()
+            .map(|row| {
+                Ok(Variant::new(
+                    &row[chrom],
+                    row[pos].parse()?,
+                    Base::try_parse(&row[refs])?,
+                    Base::try_parse(&row[alts])?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Append a column, one value for each row.
+    pub fn push_column(&mut self, name: &str, values: Vec<String>) {
+        self.header.push(String::from(name));
+        for (row, value) in self.rows.iter_mut().zip(values) {
+            row.push(value);
+        }
+    }
+
+    /// Write table to path.
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", self.header.join("\t"))?;
+        for row in &self.rows {
+            writeln!(writer, "{}", row.join("\t"))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        let table = Table::from_path(
+            "tests/variants_table.xls",
+            &["Chromosome", "Start_position", "Ref", "Alt"],
+        )
+        .unwrap();
+        assert_eq!(
+            table.variants().unwrap()[0],
+            Variant::new("1", 156843458, vec![Base::A], vec![Base::G])
+        )
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+
+use bam::record::tags::TagValue;
+use bam::record::Record as BamRecord;
+use serde::Serialize;
+
+use crate::seq::edit_distance;
+use crate::summary::{Allele, Category};
+
+/// UMI family grouping options.
+pub struct UmiOptions {
+    /// Tag holding UMI, e.g. `RX` or `MI`.
+    pub tag: [u8; 2],
+    /// Group reads by fragment start position besides UMI.
+    pub by_position: bool,
+    /// Maximum edit distance between UMIs of a family.
+    pub mismatches: usize,
+    /// Minimum family size of `Families::alt_min_size`.
+    pub min_family_size: usize,
+}
+
+/// UMI of a read, `None` if tag missing.
+///
+/// ## Warn
+///
+/// Integer tags are taken as string, e.g. `MI:i:12` as `12`.
+pub fn umi(record: &BamRecord, tag: &[u8; 2]) -> Option<Vec<u8>> {
+    match record.tags().get(tag) {
+        Some(TagValue::String(v, _)) => Some(v.to_vec()),
+        Some(TagValue::Int(v, _)) => Some(v.to_string().into_bytes()),
+        _ => None,
+    }
+}
+
+/// Fragment start of a read, the leftmost start of the read and its mate.
+pub fn fragment_start(record: &BamRecord) -> i32 {
+    if record.flag().is_paired() && record.flag().mate_is_mapped() {
+        record.start().min(record.mate_start())
+    } else {
+        record.start()
+    }
+}
+
+/// UMI family level supports, each family counted once by its consensus.
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct Families {
+    pub reference: u32,
+    pub alt: u32,
+    pub alleles: u32,
+    pub unknown: u32,
+    /// No consensus allele, e.g. as many reads supporting ref as alt.
+    pub ambiguous: u32,
+    /// Alt families of size (number of templates, mates of a read pair counted once) at least
+    /// `min_family_size`.
+    pub alt_min_size: u32,
+    /// Reads without UMI tag, not grouped into any family.
+    pub untagged: u32,
+}
+
+/// Family key, fragment start (if grouped by position) and UMI.
+type FamilyKey = (Option<i32>, Vec<u8>);
+
+/// Name and category of a read.
+type NamedRead = (Vec<u8>, Category);
+
+/// Reads to be grouped into UMI families.
+#[derive(Default)]
+pub struct UmiReads {
+    /// Names and categories of reads, by family key.
+    reads: HashMap<FamilyKey, Vec<NamedRead>>,
+    untagged: u32,
+}
+
+impl UmiReads {
+    pub fn add(&mut self, record: &BamRecord, category: Category, opts: &UmiOptions) {
+        match umi(record, &opts.tag) {
+            Some(v) => {
+                let pos = if opts.by_position {
+                    Some(fragment_start(record))
+                } else {
+                    None
+                };
+                self.reads
+                    .entry((pos, v))
+                    .or_default()
+                    .push((record.name().to_vec(), category));
+            }
+            None => self.untagged += 1,
+        }
+    }
+
+    /// Group reads into families and count families by their consensus.
+    ///
+    /// ## Note
+    ///
+    /// UMIs are visited from the largest to the smallest, a UMI within `mismatches` edits
+    /// of a visited one (and of the same position) is merged into the visited one's family.
+    pub fn families(&self, opts: &UmiOptions) -> Families {
+        let mut keys: Vec<&FamilyKey> = self.reads.keys().collect();
+        keys.sort_by(|a, b| {
+            self.reads[*b]
+                .len()
+                .cmp(&self.reads[*a].len())
+                .then(a.cmp(b))
+        });
+        let mut groups: Vec<(&FamilyKey, Vec<NamedRead>)> = Vec::new();
+        for key in keys {
+            let reads = &self.reads[key];
+            match groups
+                .iter_mut()
+                .find(|(k, _)| k.0 == key.0 && edit_distance(&k.1, &key.1) <= opts.mismatches)
+            {
+                Some((_, v)) => v.extend(reads.iter().cloned()),
+                None => groups.push((key, reads.clone())),
+            }
+        }
+
+        let mut r = Families {
+            untagged: self.untagged,
+            ..Families::default()
+        };
+        for (_, reads) in groups {
+            let cats: Vec<Category> = reads.iter().map(|i| i.1).collect();
+            match consensus(&cats) {
+                Some(Allele::Reference) => r.reference += 1,
+                Some(Allele::Alt) => {
+                    r.alt += 1;
+                    if size(&reads) >= opts.min_family_size {
+                        r.alt_min_size += 1;
+                    };
+                }
+                Some(Allele::Other) => r.alleles += 1,
+                Some(Allele::Unknown) => r.unknown += 1,
+                None => r.ambiguous += 1,
+            }
+        }
+        r
+    }
+}
+
+/// Family size, number of distinct templates by read name.
+fn size(reads: &[NamedRead]) -> usize {
+    reads.iter().map(|i| &i.0).collect::<HashSet<_>>().len()
+}
+
+/// Consensus allele of a family, the one supported by most reads, `None` if tied.
+///
+/// ## Note
+///
+/// Unknown support is taken as by `Category::informative`.
+fn consensus(cats: &[Category]) -> Option<Allele> {
+    let mut counts: HashMap<Allele, usize> = HashMap::new();
+    for each in Category::informative(cats).iter().map(|i| i.allele()) {
+        *counts.entry(each).or_default() += 1;
+    }
+    let max = counts.values().max().copied().unwrap_or_default();
+    let mut top = counts.into_iter().filter(|(_, v)| *v == max);
+    match (top.next(), top.next()) {
+        (Some((allele, _)), None) => Some(allele),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_consensus() {
+        let (r, p, m) = (Category::Reference, Category::Proper, Category::Margin);
+        assert_eq!(consensus(&[p, m, r]), Some(Allele::Alt));
+        assert_eq!(consensus(&[p, r]), None);
+        assert_eq!(consensus(&[Category::Unknown]), Some(Allele::Unknown));
+    }
+
+    #[test]
+    fn test_families() {
+        let opts = UmiOptions {
+            tag: *b"RX",
+            by_position: false,
+            mismatches: 0,
+            min_family_size: 2,
+        };
+        let mut umis = UmiReads::default();
+        let p = Category::Proper;
+        // Both mates of a template, a single molecule.
+        umis.reads.insert(
+            (None, b"AAAA".to_vec()),
+            vec![(b"r1".to_vec(), p), (b"r1".to_vec(), p)],
+        );
+        umis.reads.insert(
+            (None, b"CCCC".to_vec()),
+            vec![(b"r2".to_vec(), p), (b"r3".to_vec(), p)],
+        );
+        let families = umis.families(&opts);
+        assert_eq!((families.alt, families.alt_min_size), (2, 1));
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use bam::bam_reader::{ModificationTime, Region};
+use bam::header::Header as BamHeader;
+use bam::IndexedReader as BamReader;
+
+use crate::alias::Aliases;
+use crate::error::{err, opterr};
+use crate::fasta::Fasta;
+use crate::filter::{FlagFilter, DUPLICATE, QCFAIL, SECONDARY, SUPPLEMENTARY, UNMAPPED};
+use crate::hgvs;
+use crate::record::{self, VariantValidate};
+use crate::seq::Entry;
+use crate::summary::{Category, Summary};
+use crate::umi::UmiOptions;
+use crate::variant::Variant;
+use crate::view::View;
+
+/// Validation options.
+///
+/// ## Examples
+///
+/// ```rust
+/// # use vav::Config;
+/// let config = Config {
+///     mapq: 20,
+///     ..Config::default()
+/// };
+/// ```
+pub struct Config {
+    /// Minimum read mapping quality of proper alt support.
+    pub mapq: u8,
+    /// Minimum margin base distance of proper alt support.
+    pub margin: u32,
+    /// Minimum base quality of proper alt support.
+    pub min_baseq: u8,
+    /// Read flag filter.
+    pub filter: FlagFilter,
+    /// Count fragments besides reads.
+    pub fragments: bool,
+    /// Count UMI families besides reads if given.
+    pub umi: Option<UmiOptions>,
+    /// Background error rate of alt supporting reads.
+    pub error_rate: f64,
+    /// Keep evidence of each read in `Summary::reads`.
+    pub evidence: bool,
+    /// Keep counted reads in `Summary::records`.
+    pub records: bool,
+    /// Reference genome, to check and normalize variants, and to compare reads without MD tag.
+    pub reference: Option<Fasta>,
+    /// Chromosome aliases of variants, bam and reference genome.
+    pub aliases: Aliases,
+}
+
+impl Default for Config {
+    /// Same as command line defaults.
+    fn default() -> Self {
+        Self {
+            mapq: 30,
+            margin: 10,
+            min_baseq: 20,
+            filter: FlagFilter::new(0, UNMAPPED | SECONDARY | QCFAIL | DUPLICATE | SUPPLEMENTARY),
+            fragments: false,
+            umi: None,
+            error_rate: 0.001,
+            evidence: false,
+            records: false,
+            reference: None,
+            aliases: Aliases::default(),
+        }
+    }
+}
+
+/// Variant to validate.
+pub struct Target {
+    /// Input form, by which summaries are keyed.
+    pub key: String,
+    /// Variant validated, normalized if reference genome given.
+    pub variant: Variant,
+    /// Input and normalized forms, if normalized.
+    pub normalized: Option<(String, String)>,
+}
+
+/// Variant validator against reads of bam files.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use vav::{Config, Validator};
+/// let validator = Validator::new(Config::default());
+/// let target = validator.target("2:29474101C>A")?;
+/// let varsum = validator.validate("tests/many_variants.bam", &[target])?;
+/// println!("{}", varsum["2:29474101C>A"].alt_freq());
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct Validator {
+    config: Config,
+}
+
+impl Validator {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Parse a variant of any supported format, including HGVS genomic notation.
+    pub fn parse(&self, v: &str) -> Result<Variant, Box<dyn Error>> {
+        if hgvs::is_hgvs(v) {
+            hgvs::try_parse(v, self.config.reference.as_ref())
+        } else {
+            Variant::try_parse(v)
+        }
+    }
+
+    /// Parse a variant as target keyed by `v`, see `target_of`.
+    pub fn target(&self, v: &str) -> Result<Target, Box<dyn Error>> {
+        self.target_of(String::from(v), self.parse(v)?)
+    }
+
+    /// Target of a variant keyed by `key`, checked and normalized if reference genome given.
+    pub fn target_of(&self, key: String, variant: Variant) -> Result<Target, Box<dyn Error>> {
+        // Variants of VCF and tables are not parsed by `Variant::try_parse`.
+        if variant.pos() == 0 {
+            return Err(Box::new(err(&format!(
+                "Variant `{}` position `0` not a positive integer.",
+                key
+            ))));
+        };
+        log::warn!("Variant {} Parsed as {:?}", &key, variant);
+        let (variant, normalized) = match self.config.reference {
+            Some(ref fasta) => {
+                fasta.check(&variant)?;
+                let normalized = variant.normalize(fasta)?;
+                log::warn!("Variant {} normalized as {}", &key, normalized);
+                let forms = (variant.to_string(), normalized.to_string());
+                (normalized, Some(forms))
+            }
+            None => (variant, None),
+        };
+        Ok(Target {
+            key,
+            variant,
+            normalized,
+        })
+    }
+
+    /// Bam region of a variant, chromosome name resolved by aliases.
+    fn region(&self, variant: &Variant, header: &BamHeader) -> Result<Region, Box<dyn Error>> {
+        let name = self.config.aliases.resolve_or_suggest(
+            variant.chrom(),
+            header.reference_names(),
+            "bam",
+        )?;
+        let rid = header.reference_id(name).ok_or_else(opterr)?;
+        Ok(Region::new(rid, variant.pos(), variant.end()))
+    }
+
+    /// Validate variants against reads of a bam file, summaries keyed by target key.
+    pub fn validate(
+        &self,
+        bam: &str,
+        targets: &[Target],
+    ) -> Result<HashMap<String, Summary>, Box<dyn Error>> {
+        log::warn!("Reading bam file {}.", bam);
+        let mut sam = BamReader::build()
+            .modification_time(ModificationTime::warn(|e| eprintln!("{}", e)))
+            .from_path(bam)?;
+
+        let mut varsum: HashMap<String, Summary> = HashMap::new();
+        for target in targets {
+            let (each, variant) = (&target.key, &target.variant);
+            let mut sum = Summary::default();
+            if let Some((ref input, ref normalized)) = target.normalized {
+                sum.input = Some(input.clone());
+                sum.normalized = Some(normalized.clone());
+            };
+
+            log::warn!("Fetching variant adjcent reads.");
+            let reg = self.region(variant, sam.header())?;
+            for i in sam.fetch(&reg)? {
+                let record = i?;
+                match sum.validate(&record, variant, &self.config) {
+                    Ok(_) => {}
+                    Err(e) => {
+                        log::error!("{}", e)
+                    }
+                }
+            }
+
+            sum.conclude(&self.config);
+            log::warn!(
+                "Variant {} total {}; Ref {}({}); Alt {}({}); Proper alt {}({}); Margin alt {}({}); Lowq alt {}({}); Lowbq alt {}({}); Filtered {}; FS {}; SOR {}; AF 95% CI {}-{}; {} (LoD {})",
+                each,
+                sum.total_count(),
+                sum.reference,
+                sum.ref_freq(),
+                sum.alt_count(),
+                sum.alt_freq(),
+                sum.proper,
+                sum.proper_freq(),
+                sum.margin,
+                sum.margin_freq(),
+                sum.lowq,
+                sum.lowq_freq(),
+                sum.lowbq,
+                sum.lowbq_freq(),
+                sum.filtered.total(),
+                sum.strand_bias.fs,
+                sum.strand_bias.sor,
+                sum.detection.af_low,
+                sum.detection.af_high,
+                if sum.detection.detected {
+                    "Detected"
+                } else {
+                    "Not detected"
+                },
+                sum.detection
+                    .lod
+                    .map_or_else(|| String::from("NA"), |i| i.to_string()),
+            );
+            varsum.insert(each.clone(), sum);
+        }
+        Ok(varsum)
+    }
+
+    /// Stacked view of reads overlapping a variant, in a window of `width` bases centered on it.
+    ///
+    /// Reference bases are taken from reference genome if given, otherwise from MD tags.
+    pub fn view(&self, bam: &str, variant: &Variant, width: u32) -> Result<View, Box<dyn Error>> {
+        let mut sam = BamReader::build()
+            .modification_time(ModificationTime::warn(|e| eprintln!("{}", e)))
+            .from_path(bam)?;
+        let start = (variant.pos() + 1).saturating_sub(width / 2).max(1);
+        let mut view = View::new(variant, start, width);
+        if let Some(ref fasta) = self.config.reference {
+            let from = (start - 1) as u64;
+            view.set_reference(&fasta.fetch(variant.chrom(), from, from + width as u64)?);
+        };
+
+        let reg = self.region(variant, sam.header())?;
+        for i in sam.fetch(&reg)? {
+            let record = i?;
+            if ((record.start() + 1) as u32 > variant.end())
+                || ((record.calculate_end() as u32) < variant.pos())
+            {
+                continue;
+            };
+            let category = match self.config.filter.reason(&record.flag()) {
+                Some(_) => None,
+                None => match record.validate(variant, &self.config)? {
+                    Some(ref v) => Some(Category::from(v)),
+                    None => continue,
+                },
+            };
+            let entries = match record::entries(&record, variant, self.config.reference.as_ref())? {
+                Some(v) => v,
+                None => {
+                    let seq = record.sequence();
+                    record
+                        .aligned_pairs()
+                        .map(|(q, r)| Entry::new(q, q.map(|i| seq.at(i as usize)), r, None))
+                        .collect()
+                }
+            };
+            view.add(&record, &entries, category);
+        }
+        Ok(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        let validator = Validator::new(Config::default());
+        let target = validator.target("chr1-12345-AT-A").unwrap();
+        assert_eq!(target.key, "chr1-12345-AT-A");
+        assert_eq!(target.variant.to_string(), "chr1:12345AT>A");
+        assert!(target.normalized.is_none());
+        assert!(validator
+            .target_of(
+                String::from("1 0"),
+                Variant::new("1", 0, Vec::new(), Vec::new())
+            )
+            .is_err());
+        // Deleted bases required from reference genome.
+        assert!(validator.target("chr1:g.123del").is_err());
+    }
+}
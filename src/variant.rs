@@ -4,6 +4,7 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::error::{err, opterr};
+use crate::fasta::Fasta;
 use crate::seq::{Base, Ordering};
 
 static VAREX: Lazy<Regex> = Lazy::new(|| {
@@ -19,33 +20,127 @@ pub struct Variant {
 }
 
 impl Variant {
+    pub fn new(chrom: &str, pos: u32, refs: Vec<Base>, alts: Vec<Base>) -> Self {
+        Self {
+            chrom: String::from(chrom),
+            pos,
+            refs,
+            alts,
+        }
+    }
+
     /// # Parse variant from a string.
     ///
     /// ## Format
     ///
     /// > chr1:12345AT>G
+    /// > chr1-12345-AT-G
+    /// > chr1:12345:AT:G
+    /// > chr1 12345 . AT G
+    ///
+    /// The last is a VCF line, whitespace separated, columns after ALT ignored.
     ///
     /// ## Examples
     ///
     /// ```rust
+    /// # use vav::Variant;
     /// Variant::try_parse("1:12345AT>GC")?;
+    /// Variant::try_parse("1-12345-AT-GC")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn try_parse(v: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        if let Some(c) = VAREX.captures(v) {
-            Ok(Self {
-                chrom: String::from(c.name("chrom").ok_or_else(opterr)?.as_str()),
-                pos: c.name("pos").ok_or_else(opterr)?.as_str().parse()?,
-                refs: Base::try_parse(c.name("refs").ok_or_else(opterr)?.as_str())?,
-                alts: Base::try_parse(c.name("alts").ok_or_else(opterr)?.as_str())?,
-            })
+        let v = v.trim();
+        if v.split_whitespace().nth(1).is_some() {
+            let fields: Vec<&str> = v.split_whitespace().collect();
+            if fields.len() < 5 {
+                return Err(Box::new(err(&format!(
+                    "Error parsing `{}` as a VCF line variant like `chr1 12345 . AT G`: expecting at least 5 columns, found {}.",
+                    v,
+                    fields.len()
+                ))));
+            };
+            Self::from_fields(v, "VCF line", [fields[0], fields[1], fields[3], fields[4]])
+        } else if v.contains('>') {
+            match VAREX.captures(v) {
+                Some(c) if c.name("pos").is_some_and(|i| i.as_str().parse() == Ok(0u32)) => {
+                    Err(Box::new(err(&format!(
+                        "Error parsing `{}` as a variant like `chr1:12345AT>G`: position `0` not a positive integer.",
+                        v
+                    ))))
+                }
+                Some(c) => Ok(Self {
+                    chrom: String::from(c.name("chrom").ok_or_else(opterr)?.as_str()),
+                    pos: c.name("pos").ok_or_else(opterr)?.as_str().parse()?,
+                    refs: Base::try_parse(c.name("refs").ok_or_else(opterr)?.as_str())?,
+                    alts: Base::try_parse(c.name("alts").ok_or_else(opterr)?.as_str())?,
+                }),
+                None => Err(Box::new(err(&format!(
+                    "Error parsing `{}` as a variant like `chr1:12345AT>G`.",
+                    v
+                )))),
+            }
+        } else if v.matches(':').count() == 3 {
+            let mut fields = v.splitn(4, ':');
+            let mut next = || fields.next().unwrap_or_default();
+            Self::from_fields(v, "colon separated", [next(), next(), next(), next()])
+        } else if v.matches('-').count() >= 3 {
+            // Chromosome may contain `-`, split from the right.
+            let mut fields = v.rsplitn(4, '-');
+            let (alts, refs, pos, chrom) = (
+                fields.next().unwrap_or_default(),
+                fields.next().unwrap_or_default(),
+                fields.next().unwrap_or_default(),
+                fields.next().unwrap_or_default(),
+            );
+            Self::from_fields(v, "gnomAD style", [chrom, pos, refs, alts])
         } else {
             Err(Box::new(err(&format!(
-                "Error parsing `{}` as a Variant",
+                "Error parsing `{}` as a variant, expecting `chr1:12345AT>G`, `chr1-12345-AT-G`, `chr1:12345:AT:G` or `chr1 12345 . AT G`.",
                 v
             ))))
         }
     }
 
+    /// Variant from chromosome, position, ref and alt fields of an input `v` in `format`.
+    fn from_fields(
+        v: &str,
+        format: &str,
+        [chrom, pos, refs, alts]: [&str; 4],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let invalid = |reason: String| {
+            Box::new(err(&format!(
+                "Error parsing `{}` as a {} variant: {}.",
+                v, format, reason
+            )))
+        };
+        if chrom.is_empty() {
+            return Err(invalid(String::from("empty chromosome")));
+        };
+        let pos = match pos.parse() {
+            Ok(v) if v > 0 => v,
+            _ => {
+                return Err(invalid(format!(
+                    "position `{}` not a positive integer",
+                    pos
+                )))
+            }
+        };
+        if alts.contains(',') {
+            return Err(invalid(format!(
+                "multi-allelic alt `{}`, use --vcf instead",
+                alts
+            )));
+        };
+        Ok(Self {
+            chrom: String::from(chrom),
+            pos,
+            refs: Base::try_parse(refs)
+                .map_err(|_| invalid(format!("ref `{}` not a sequence of ATCGN", refs)))?,
+            alts: Base::try_parse(alts)
+                .map_err(|_| invalid(format!("alt `{}` not a sequence of ATCGN", alts)))?,
+        })
+    }
+
     /// Reference chromosome.
     pub fn chrom(&self) -> &String {
         &self.chrom
@@ -105,6 +200,84 @@ impl Variant {
         }
     }
 
+    /// Normalize against reference genome, trimming shared bases and left aligning indels.
+    ///
+    /// ## Examples
+    ///
+    /// With reference `GCAAAAT` from position `1`:
+    ///
+    /// ```rust,no_run
+    /// # use vav::alias::Aliases;
+    /// # use vav::fasta::Fasta;
+    /// # use vav::Variant;
+    /// # let fasta = Fasta::from_path("genome.fa", Aliases::default())?;
+    /// let var = Variant::try_parse("1:5AA>A")?.normalize(&fasta)?;
+    /// assert_eq!(var.to_string(), "1:2CA>C");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// ## Note
+    ///
+    /// Same as `vt normalize` and `bcftools norm`, indels are anchored by their previous base,
+    /// abbreviated indels like `1:12345C>-` are anchored by base at `12345` first.
+    pub fn normalize(&self, fasta: &Fasta) -> Result<Self, Box<dyn std::error::Error>> {
+        if self.refs == self.alts {
+            return Ok(Self::new(
+                &self.chrom,
+                self.pos,
+                self.refs.clone(),
+                self.alts.clone(),
+            ));
+        };
+        let base = |pos: u32| -> Result<Base, Box<dyn std::error::Error>> {
+            let v = fasta.fetch(&self.chrom, pos as u64 - 1, pos as u64)?;
+            Ok(Base::from_byte(*v.first().ok_or_else(|| {
+                err(&format!(
+                    "Position {}:{} out of reference.",
+                    self.chrom, pos
+                ))
+            })?)?)
+        };
+        let (mut pos, mut refs, mut alts) = (self.pos, self.refs.clone(), self.alts.clone());
+        if refs.is_empty() || alts.is_empty() {
+            let anchor = base(pos)?;
+            refs.insert(0, anchor.clone());
+            alts.insert(0, anchor);
+        };
+        loop {
+            if !refs.is_empty() && !alts.is_empty() && refs.last() == alts.last() {
+                refs.pop();
+                alts.pop();
+            } else if (refs.is_empty() || alts.is_empty()) && pos > 1 {
+                pos -= 1;
+                let anchor = base(pos)?;
+                refs.insert(0, anchor.clone());
+                alts.insert(0, anchor);
+            } else {
+                break;
+            }
+        }
+        while refs.len() > 1 && alts.len() > 1 && refs[0] == alts[0] {
+            refs.remove(0);
+            alts.remove(0);
+            pos += 1;
+        }
+        Ok(Self::new(&self.chrom, pos, refs, alts))
+    }
+
+    /// 0-based reference start of `refs`.
+    ///
+    /// ## Warn
+    ///
+    /// Abbreviated indels like `1:12345C>-` and `1:12345->C` start after `pos`.
+    pub fn ref_start(&self) -> u32 {
+        if self.refs.is_empty() || self.alts.is_empty() {
+            self.pos
+        } else {
+            self.pos - 1
+        }
+    }
+
     pub fn is_abbr_deletion(&self) -> bool {
         self.alts.is_empty()
     }
@@ -154,6 +327,7 @@ impl fmt::Display for Variant {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::fasta::test::fasta;
 
     #[test]
     fn test_try_parse() {
@@ -167,4 +341,50 @@ mod test {
             }
         )
     }
+
+    #[test]
+    fn test_try_parse_formats() {
+        for each in [
+            "chr1-12345-AT-GC",
+            "chr1:12345:AT:GC",
+            "chr1 12345 . AT GC",
+            "chr1\t12345\trs1\tAT\tGC\t50\tPASS\t.",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                Variant::try_parse(each).unwrap().to_string(),
+                "chr1:12345AT>GC"
+            );
+        }
+        let e = Variant::try_parse("chr1-12345-AX-G")
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("gnomAD style") && e.contains("ref `AX`"));
+        let e = Variant::try_parse("chr1 12345 . A G,T")
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("VCF line") && e.contains("multi-allelic"));
+        for each in ["chr1-0-A-G", "chr1:0:A:G", "chr1 0 . A G", "chr1:0A>G"].iter() {
+            let e = Variant::try_parse(each).unwrap_err().to_string();
+            assert!(e.contains("position `0` not a positive integer"), "{}", e);
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        let fasta = fasta("norm", ">1\nGCAAAATCACACG\n", "1\t13\t3\t13\t14\n");
+        let norm = |v: &str| {
+            Variant::try_parse(v)
+                .unwrap()
+                .normalize(&fasta)
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(norm("1:5AA>A"), "1:2CA>C");
+        assert_eq!(norm("1:5A>-"), "1:2CA>C");
+        assert_eq!(norm("1:6->A"), "1:2C>CA");
+        assert_eq!(norm("1:10CAC>C"), "1:7TCA>T");
+        assert_eq!(norm("1:3AAAA>GAAA"), "1:3A>G");
+    }
 }
@@ -0,0 +1,380 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+
+use bam::bgzip::{ConsecutiveReader, Writer as BgzipWriter};
+
+use crate::bcf;
+use crate::error::err;
+use crate::seq::Base;
+use crate::variant::Variant;
+
+/// Gzip magic bytes, bgzip is a special gzip.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Magic of BCF, after decompressed.
+const BCF_MAGIC: &[u8] = b"BCF";
+
+/// Percent-encode characters of FORMAT value reserved by VCF, `:` the field separator.
+fn encode(v: &str) -> String {
+    v.replace('%', "%25").replace(':', "%3A")
+}
+
+/// Decode FORMAT value encoded by `encode`.
+fn decode(v: &str) -> String {
+    v.replace("%3A", ":").replace("%25", "%")
+}
+
+/// VCF data line.
+///
+/// Fields are kept verbatim, so that the line can be written back as it was.
+#[derive(Debug)]
+pub struct VcfRecord {
+    fields: Vec<String>,
+}
+
+impl VcfRecord {
+    /// Parse a VCF data line.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use vav::vcf::VcfRecord;
+    /// VcfRecord::try_parse("1\t12345\t.\tAT\tG,ATT\t.\t.\t.")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_parse(line: &str) -> Result<Self, Box<dyn Error>> {
+        let fields: Vec<String> = line.split('\t').map(String::from).collect();
+        if fields.len() < 8 {
+            return Err(Box::new(err(&format!(
+                "Error parsing `{}` as a VCF record, at least 8 columns expected.",
+                line
+            ))));
+        };
+        Ok(Self { fields })
+    }
+
+    /// Chromosome, column `CHROM`.
+    pub fn chrom(&self) -> &str {
+        &self.fields[0]
+    }
+
+    /// 1-based position, column `POS`.
+    pub fn pos(&self) -> Result<u32, Box<dyn Error>> {
+        Ok(self.fields[1].parse()?)
+    }
+
+    /// Reference allele, column `REF`.
+    pub fn refs(&self) -> &str {
+        &self.fields[3]
+    }
+
+    /// Alternative alleles, column `ALT` splitted by `,`.
+    pub fn alts(&self) -> Vec<&str> {
+        self.fields[4].split(',').collect()
+    }
+
+    /// Set INFO field `key`, replacing the existing one if any.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// # use vav::vcf::VcfRecord;
+    /// # let mut record = VcfRecord::try_parse("1\t12345\t.\tAT\tG\t.\t.\t.")?;
+    /// record.set_info("VAV_AF", "0.0027");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_info(&mut self, key: &str, value: &str) {
+        let prefix = format!("{}=", key);
+        let mut info: Vec<&str> = self.fields[7]
+            .split(';')
+            .filter(|i| *i != "." && !i.is_empty() && *i != key && !i.starts_with(&prefix))
+            .collect();
+        let field = format!("{}={}", key, value);
+        info.push(&field);
+        self.fields[7] = info.join(";");
+    }
+
+    /// FORMAT field `key` of the `sample`th (0-based) sample, decoded, `None` if missing.
+    pub fn format(&self, sample: usize, key: &str) -> Option<String> {
+        let index = self.fields.get(8)?.split(':').position(|i| i == key)?;
+        self.fields
+            .get(9 + sample)?
+            .split(':')
+            .nth(index)
+            .filter(|i| *i != ".")
+            .map(decode)
+    }
+
+    /// Set FORMAT field `key` of the `sample`th (0-based) sample, replacing the existing one if any.
+    ///
+    /// ## Note
+    ///
+    /// - Missing FORMAT and sample columns are filled with `.`, trailing fields of other samples
+    ///   are left dropped as VCF allowed.
+    /// - `:` and `%` of value are percent-encoded, e.g. `1:2CA>C` as `1%3A2CA>C`.
+    pub fn set_format(&mut self, sample: usize, key: &str, value: &str) {
+        let value = encode(value);
+        if self.fields.len() < 10 + sample {
+            self.fields.resize(10 + sample, String::from("."));
+        };
+        let mut keys: Vec<&str> = self.fields[8].split(':').filter(|i| *i != ".").collect();
+        let index = match keys.iter().position(|i| *i == key) {
+            Some(v) => v,
+            None => {
+                keys.push(key);
+                keys.len() - 1
+            }
+        };
+        let format = keys.join(":");
+        let mut values: Vec<&str> = self.fields[9 + sample].split(':').collect();
+        if values.len() <= index {
+            values.resize(index + 1, ".");
+        };
+        values[index] = &value;
+        self.fields[9 + sample] = values.join(":");
+        self.fields[8] = format;
+    }
+
+    /// Convert record into variants, one for each ALT.
+    ///
+    /// ## Warn
+    ///
+    /// Symbolic (`<DEL>`), breakend, missing (`.`) and overlapping deletion (`*`) alleles
+    /// are not validatable and are skipped, result position stays `None`.
+    pub fn variants(&self) -> Result<Vec<Option<Variant>>, Box<dyn Error>> {
+        let pos = self.pos()?;
+        let refs = Base::try_parse(self.refs())?;
+        let mut r: Vec<Option<Variant>> = Vec::new();
+        for alt in self.alts() {
+            if alt == "." || alt == "*" || alt.contains(&['<', '[', ']'][..]) {
+                log::warn!(
+                    "Skipping allele `{}` of VCF record {}:{}, not a sequence.",
+                    alt,
+                    self.chrom(),
+                    pos
+                );
+                r.push(None);
+                continue;
+            };
+            r.push(Some(Variant::new(
+                self.chrom(),
+                pos,
+                refs.clone(),
+                Base::try_parse(alt)?,
+            )));
+        }
+        Ok(r)
+    }
+}
+
+/// VCF file content.
+#[derive(Debug)]
+pub struct Vcf {
+    /// Meta-information lines, those starting with `##`.
+    meta: Vec<String>,
+    /// Header line, the one starting with `#CHROM`.
+    header: String,
+    records: Vec<VcfRecord>,
+}
+
+impl Vcf {
+    /// Read VCF from path, plain text or bgzipped, or BCF.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust,no_run
+    /// # use vav::vcf::Vcf;
+    /// let vcf = Vcf::from_path("tests/calls.vcf.gz")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// ## Note
+    ///
+    /// BCF records are read as VCF data lines, e.g. written back as VCF.
+    pub fn from_path(path: &str) -> Result<Self, Box<dyn Error>> {
+        let mut magic = [0u8; 2];
+        let compressed = File::open(path)?.read(&mut magic)? == 2 && magic == GZIP_MAGIC;
+        let mut reader: Box<dyn BufRead> = if compressed {
+            Box::new(BufReader::new(ConsecutiveReader::from_path(path, 0)?))
+        } else {
+            Box::new(BufReader::new(File::open(path)?))
+        };
+        if reader.fill_buf()?.starts_with(BCF_MAGIC) {
+            let (text, lines) = bcf::read(reader)?;
+            let text: Vec<String> = text.lines().map(String::from).collect();
+            return Self::from_lines(text.into_iter().chain(lines).map(Ok));
+        };
+        Self::from_lines(reader.lines())
+    }
+
+    fn from_lines<I>(lines: I) -> Result<Self, Box<dyn Error>>
+    where
+        I: Iterator<Item = std::io::Result<String>>,
+    {
+        let mut meta: Vec<String> = Vec::new();
+        let mut header = String::new();
+        let mut records: Vec<VcfRecord> = Vec::new();
+        for line in lines {
+            let line = line?;
+            if line.starts_with("##") {
+                meta.push(line);
+            } else if line.starts_with('#') {
+                header = line;
+            } else if !line.is_empty() {
+                records.push(VcfRecord::try_parse(&line)?);
+            }
+        }
+        if header.is_empty() {
+            return Err(Box::new(err("VCF header line `#CHROM ...` missing.")));
+        };
+        Ok(Self {
+            meta,
+            header,
+            records,
+        })
+    }
+
+    pub fn records(&self) -> &Vec<VcfRecord> {
+        &self.records
+    }
+
+    pub fn records_mut(&mut self) -> &mut Vec<VcfRecord> {
+        &mut self.records
+    }
+
+    /// Add an `##INFO` meta line, replacing the existing one with the same ID.
+    pub fn set_info_meta(&mut self, id: &str, number: &str, kind: &str, description: &str) {
+        self.set_meta("INFO", id, number, kind, description)
+    }
+
+    /// Add a `##FORMAT` meta line, replacing the existing one with the same ID.
+    pub fn set_format_meta(&mut self, id: &str, number: &str, kind: &str, description: &str) {
+        self.set_meta("FORMAT", id, number, kind, description)
+    }
+
+    fn set_meta(&mut self, section: &str, id: &str, number: &str, kind: &str, description: &str) {
+        let prefix = format!("##{}=<ID={},", section, id);
+        self.meta.retain(|i| !i.starts_with(&prefix));
+        self.meta.push(format!(
+            "{}Number={},Type={},Description=\"{}\">",
+            prefix, number, kind, description
+        ));
+    }
+
+    /// Sample names of the header line.
+    pub fn samples(&self) -> Vec<&str> {
+        self.header.split('\t').skip(9).collect()
+    }
+
+    /// 0-based index of a sample column, the column appended if not present.
+    pub fn sample_index(&mut self, name: &str) -> usize {
+        let mut columns: Vec<&str> = self.header.split('\t').collect();
+        if columns.len() < 9 {
+            columns.resize(8, ".");
+            columns.push("FORMAT");
+        };
+        let index = match columns[9..].iter().position(|i| *i == name) {
+            Some(v) => v,
+            None => {
+                columns.push(name);
+                columns.len() - 10
+            }
+        };
+        self.header = columns.join("\t");
+        index
+    }
+
+    /// Add a meta line as is, e.g. `##vavCommand=...`.
+    pub fn push_meta(&mut self, line: String) {
+        self.meta.push(line);
+    }
+
+    /// Write VCF to path, bgzipped if path ends with `.gz`.
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        if path.ends_with(".gz") {
+            let mut writer = BgzipWriter::from_path(path)?;
+            self.write_to(&mut writer)?;
+            writer.finish()?;
+        } else {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_to(&mut writer)?;
+            writer.flush()?;
+        };
+        Ok(())
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), Box<dyn Error>> {
+        for line in &self.meta {
+            writeln!(writer, "{}", line)?;
+        }
+        writeln!(writer, "{}", self.header)?;
+        for record in &self.records {
+            writeln!(writer, "{}", record.fields.join("\t"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_record_variants() {
+        let record = VcfRecord::try_parse("1\t12345\t.\tAT\tG,<DEL>,A\t.\t.\t.").unwrap();
+        assert_eq!(
+            record.variants().unwrap(),
+            vec![
+                Some(Variant::new(
+                    "1",
+                    12345,
+                    vec![Base::A, Base::T],
+                    vec![Base::G]
+                )),
+                None,
+                Some(Variant::new(
+                    "1",
+                    12345,
+                    vec![Base::A, Base::T],
+                    vec![Base::A]
+                )),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_record_set_info() {
+        let mut record = VcfRecord::try_parse("1\t12345\t.\tA\tG\t.\t.\tDP=10;VAV_AF=0.1").unwrap();
+        record.set_info("VAV_AF", "0.5");
+        assert_eq!(record.fields[7], "DP=10;VAV_AF=0.5")
+    }
+
+    #[test]
+    fn test_record_set_format() {
+        let mut record = VcfRecord::try_parse("1\t12345\t.\tA\tG\t.\t.\t.").unwrap();
+        record.set_format(1, "VAV_REF", "3");
+        record.set_format(0, "VAV_ALT", "2");
+        assert_eq!(record.fields[8..], ["VAV_REF:VAV_ALT", ".:2", "3"]);
+        assert_eq!(record.format(0, "VAV_ALT").as_deref(), Some("2"));
+        assert_eq!(record.format(0, "VAV_REF"), None);
+        assert_eq!(record.format(2, "VAV_REF"), None);
+    }
+    #[test]
+    fn test_record_set_format_samples() {
+        let mut record = VcfRecord::try_parse("1\t2\t.\tCAA\tCA\t.\t.\t.").unwrap();
+        for (sample, norm) in [(0, "1:2CA>C"), (1, ".")].iter() {
+            record.set_format(*sample, "VAV_REF", "3");
+            record.set_format(*sample, "VAV_NORM", norm);
+            record.set_format(*sample, "VAV_AF", "0.5");
+        }
+        let line = record.fields.join("\t");
+        let parsed = VcfRecord::try_parse(&line).unwrap();
+        let n = parsed.fields[8].split(':').count();
+        assert!(parsed.fields[9..].iter().all(|i| i.split(':').count() == n));
+        assert_eq!(parsed.format(0, "VAV_NORM").as_deref(), Some("1:2CA>C"));
+        assert_eq!(parsed.format(0, "VAV_AF").as_deref(), Some("0.5"));
+        assert_eq!(parsed.format(1, "VAV_NORM"), None);
+        assert_eq!(parsed.format(1, "VAV_AF").as_deref(), Some("0.5"));
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use bam::record::Record as BamRecord;
+
+use crate::seq::Entry;
+use crate::summary::Category;
+use crate::variant::Variant;
+
+/// ANSI escape of reset.
+const RESET: &str = "\x1b[0m";
+
+/// Base of a read at a reference position.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Cell {
+    /// Not covered by read.
+    Empty,
+    Match,
+    Mismatch(u8),
+    Deletion,
+    /// Read base of unknown reference, neither MD tag nor reference genome available.
+    Unknown(u8),
+}
+
+/// A read in view, cells and inserted bases by window position.
+struct Row {
+    reverse: bool,
+    start: i32,
+    cells: Vec<Cell>,
+    /// Bases inserted after each window position.
+    insertions: Vec<Vec<u8>>,
+}
+
+/// Stacked view of reads around a variant, in the style of `samtools tview`.
+///
+/// ## Examples
+///
+/// ```rust,no_run
+/// # use vav::{Config, Validator};
+/// let validator = Validator::new(Config::default());
+/// let target = validator.target("1:300T>A")?;
+/// let view = validator.view("tumor.bam", &target.variant, 60)?;
+/// print!("{}", view.render(true, 20));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// ## Format
+///
+/// Reads are grouped by category, forward reads before reverse ones. Matches are shown as `.` on
+/// forward strand and `,` on reverse strand, mismatches and insertions as bases, uppercase on
+/// forward strand and lowercase on reverse strand, deletions as `*`.
+pub struct View {
+    title: String,
+    /// 1-based first position of window.
+    start: u32,
+    /// Window positions of variant ref, inclusive.
+    variant: (usize, usize),
+    /// Reference bases by window position, `None` if unknown.
+    reference: Vec<Option<u8>>,
+    /// Reads by category, filtered reads as `None`.
+    groups: BTreeMap<(bool, Option<Category>), Vec<Row>>,
+}
+
+impl View {
+    /// View of `width` bases from 1-based `start`.
+    pub fn new(var: &Variant, start: u32, width: u32) -> Self {
+        let index = |pos: u32| (pos.max(start) - start) as usize;
+        let first = var.ref_start() + 1;
+        Self {
+            title: format!(
+                "{} {}:{}-{}",
+                var,
+                var.chrom(),
+                start,
+                start + width.max(1) - 1
+            ),
+            start,
+            variant: (index(first), index(var.end().max(first))),
+            reference: vec![None; width as usize],
+            groups: BTreeMap::new(),
+        }
+    }
+
+    /// Set reference bases from the first window position, e.g. from reference genome.
+    pub fn set_reference(&mut self, bases: &[u8]) {
+        for (v, b) in self.reference.iter_mut().zip(bases) {
+            *v = Some(b.to_ascii_uppercase());
+        }
+    }
+
+    /// Window position of 0-based reference position.
+    fn index(&self, ref_pos: u32) -> Option<usize> {
+        let i = (ref_pos + 1).checked_sub(self.start)? as usize;
+        if i < self.reference.len() {
+            Some(i)
+        } else {
+            None
+        }
+    }
+
+    /// Add a read by its alignment entries, filtered reads with `category` `None`.
+    pub fn add(&mut self, record: &BamRecord, entries: &[Entry], category: Option<Category>) {
+        let width = self.reference.len();
+        let reverse = record.flag().is_reverse_strand();
+        let mut row = Row {
+            reverse,
+            start: record.start(),
+            cells: vec![Cell::Empty; width],
+            insertions: vec![Vec::new(); width],
+        };
+        // Soft clips are entries without reference position too, but out of alignment.
+        let clipped = record.cigar().soft_clipping(false) as usize;
+        let aligned = &entries[..entries.len().saturating_sub(clipped)];
+        let mut last: Option<usize> = None;
+        let mut started = false;
+        for entry in aligned {
+            let ref_pos = match entry.ref_pos() {
+                Some(v) => v,
+                None => {
+                    if let (true, Some(i), Some(nt)) = (started, last, entry.record_nt()) {
+                        row.insertions[i].push(nt);
+                    };
+                    continue;
+                }
+            };
+            started = true;
+            last = self.index(ref_pos);
+            let i = match last {
+                Some(v) => v,
+                None => continue,
+            };
+            if self.reference[i].is_none() {
+                self.reference[i] = entry.ref_nt().map(|b| b.to_ascii_uppercase());
+            };
+            row.cells[i] = match (entry.record_nt(), entry.ref_nt()) {
+                (None, _) => Cell::Deletion,
+                (Some(_), Some(_)) if entry.is_seq_match() => Cell::Match,
+                (Some(a), Some(_)) => Cell::Mismatch(a),
+                (Some(a), None) => Cell::Unknown(a),
+            };
+        }
+        self.groups
+            .entry((category.is_none(), category))
+            .or_default()
+            .push(row);
+    }
+
+    /// Render as text, colored by ANSI escapes if `color`, at most `max_reads` reads a category.
+    pub fn render(&self, color: bool, max_reads: usize) -> String {
+        let width = self.reference.len();
+        // Columns of insertions after each window position.
+        let mut spans = vec![0; width];
+        for row in self.groups.values().flatten() {
+            for (span, inserted) in spans.iter_mut().zip(&row.insertions) {
+                *span = (*span).max(inserted.len());
+            }
+        }
+        let paint = |s: &str, code: &str| {
+            if color && !code.is_empty() {
+                format!("\x1b[{}m{}{}", code, s, RESET)
+            } else {
+                String::from(s)
+            }
+        };
+
+        let mut r = String::new();
+        writeln!(r, "{}", self.title).ok();
+        let mut marker = String::new();
+        let mut line = String::new();
+        for (i, span) in spans.iter().enumerate() {
+            let within = self.variant.0 <= i && i <= self.variant.1;
+            marker.push(if within { 'v' } else { ' ' });
+            marker.push_str(&" ".repeat(*span));
+            let base = (self.reference[i].unwrap_or(b'N') as char).to_string();
+            line.push_str(&paint(&base, if within { "7" } else { "" }));
+            line.push_str(&"*".repeat(*span));
+        }
+        writeln!(r, "{}", marker.trim_end()).ok();
+        writeln!(r, "{}", line).ok();
+
+        for ((_, category), rows) in &self.groups {
+            let name = category.map_or("filtered", |i| i.as_str());
+            writeln!(r, "# {} ({})", name, rows.len()).ok();
+            let mut sorted: Vec<&Row> = rows.iter().collect();
+            sorted.sort_by_key(|i| (i.reverse, i.start));
+            for row in sorted.iter().take(max_reads) {
+                let mut text = String::new();
+                for (i, span) in spans.iter().enumerate() {
+                    text.push_str(&self.cell(row, i, &paint));
+                    let inserted = &row.insertions[i];
+                    if !inserted.is_empty() {
+                        let bases: String =
+                            inserted.iter().map(|b| case(*b, row.reverse)).collect();
+                        text.push_str(&paint(&bases, "1;35"));
+                        text.push_str(&"*".repeat(span - inserted.len()));
+                    } else if *span > 0 {
+                        let within = row.cells[i] != Cell::Empty
+                            && matches!(row.cells.get(i + 1), Some(c) if *c != Cell::Empty);
+                        text.push_str(&(if within { "*" } else { " " }).repeat(*span));
+                    };
+                }
+                writeln!(r, "{}", text.trim_end()).ok();
+            }
+            if rows.len() > max_reads {
+                writeln!(r, "... {} more", rows.len() - max_reads).ok();
+            };
+        }
+        r
+    }
+
+    /// Text of a read cell, mismatches colored by base and deletions highlighted.
+    fn cell(&self, row: &Row, i: usize, paint: &dyn Fn(&str, &str) -> String) -> String {
+        match row.cells[i] {
+            Cell::Empty => String::from(" "),
+            Cell::Match => String::from(if row.reverse { "," } else { "." }),
+            Cell::Mismatch(b) => {
+                let code = match b.to_ascii_uppercase() {
+                    b'A' => "1;32",
+                    b'C' => "1;34",
+                    b'G' => "1;33",
+                    b'T' => "1;31",
+                    _ => "1",
+                };
+                paint(&case(b, row.reverse).to_string(), code)
+            }
+            Cell::Deletion => paint("*", "1;36"),
+            Cell::Unknown(b) => case(b, row.reverse).to_string(),
+        }
+    }
+}
+
+/// Base uppercased on forward strand, lowercased on reverse strand.
+fn case(b: u8, reverse: bool) -> char {
+    if reverse {
+        b.to_ascii_lowercase() as char
+    } else {
+        b.to_ascii_uppercase() as char
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let var = Variant::try_parse("1:103T>A").unwrap();
+        let mut view = View::new(&var, 101, 6);
+        view.set_reference(b"ACTGAC");
+        let entry = |q: Option<u32>, r: Option<u32>, nt: Option<u8>| {
+            Entry::new(q, nt, r, r.map(|i| b"ACTGAC"[(i - 100) as usize]))
+        };
+        let mut record = BamRecord::new();
+        record.set_start(100);
+        let entries = vec![
+            entry(Some(0), Some(100), Some(b'A')),
+            entry(Some(1), Some(101), Some(b'C')),
+            entry(Some(2), Some(102), Some(b'A')),
+            entry(Some(3), None, Some(b'G')),
+            entry(None, Some(103), None),
+            entry(Some(4), Some(104), Some(b'A')),
+        ];
+        view.add(&record, &entries, Some(Category::Proper));
+        view.add(&record, &entries[..2], None);
+        assert_eq!(
+            view.render(false, 10),
+            "1:103T>A 1:101-106\n  v\nACT*GAC\n# proper (1)\n..AG*.\n# filtered (1)\n..\n"
+        );
+    }
+}